@@ -16,6 +16,10 @@
 #![cfg_attr(not(target_os = "riot"), feature(const_ip))]
 #![feature(const_fn)]
 #![feature(box_syntax)]
+#![feature(try_reserve)]
+#![feature(futures_api)]
+#![feature(pin)]
+#![feature(arbitrary_self_types)]
 #![feature(extern_crate_item_prelude)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -42,6 +46,9 @@ pub(crate) mod sys;
 ///
 pub mod thread;
 
+#[cfg(feature = "std")]
+pub use std::thread_local;
+
 /// Provide syncronizations primitives of the underlying OS.
 pub mod sync;
 
@@ -51,3 +58,5 @@ pub mod net;
 pub mod time;
 #[macro_use]
 pub mod io;
+/// Execution of futures.
+pub mod task;
@@ -0,0 +1,114 @@
+use core::iter::Cloned;
+use core::option;
+use core::slice;
+
+use super::{IpAddress, Ipv4Address, Ipv6Address, SocketAddr};
+use crate::io;
+
+/// Conversion into one or more socket addresses, e.g. to [bind] a socket.
+///
+/// The counterpart of `std::net::ToSocketAddrs`. Host names are only resolved on OS with a
+/// standard library.
+///
+/// # Examples
+/// ```no_run
+/// use os_glue::net::{UdpSocket, IPV6_UNSPECIFIED};
+///
+/// // Bound to an ephemeral port.
+/// let socket = UdpSocket::bind((IPV6_UNSPECIFIED, 0)).unwrap();
+/// let port = socket.local_addr().unwrap().port();
+/// ```
+///
+/// [bind]: super::UdpSocket::bind
+pub trait ToSocketAddrs {
+    type Iter: Iterator<Item = SocketAddr>;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error>;
+}
+
+impl ToSocketAddrs for SocketAddr {
+    type Iter = option::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error> {
+        Ok(Some(*self).into_iter())
+    }
+}
+
+impl ToSocketAddrs for (IpAddress, u16) {
+    type Iter = option::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error> {
+        SocketAddr::new(self.0, self.1).to_socket_addrs()
+    }
+}
+
+impl ToSocketAddrs for (Ipv6Address, u16) {
+    type Iter = option::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error> {
+        (IpAddress::from(self.0), self.1).to_socket_addrs()
+    }
+}
+
+impl ToSocketAddrs for (Ipv4Address, u16) {
+    type Iter = option::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error> {
+        (IpAddress::from(self.0), self.1).to_socket_addrs()
+    }
+}
+
+impl<'a> ToSocketAddrs for &'a [SocketAddr] {
+    type Iter = Cloned<slice::Iter<'a, SocketAddr>>;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error> {
+        Ok(self.iter().cloned())
+    }
+}
+
+impl<'a, T> ToSocketAddrs for &'a T
+where
+    T: ToSocketAddrs + ?Sized,
+{
+    type Iter = T::Iter;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error> {
+        (**self).to_socket_addrs()
+    }
+}
+
+#[cfg(feature = "std")]
+impl ToSocketAddrs for str {
+    type Iter = std::vec::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error> {
+        std::net::ToSocketAddrs::to_socket_addrs(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> ToSocketAddrs for (&'a str, u16) {
+    type Iter = std::vec::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> Result<Self::Iter, io::Error> {
+        std::net::ToSocketAddrs::to_socket_addrs(self)
+    }
+}
+
+/// Runs `f` on each address until it succeeds, returning the last error otherwise.
+pub(crate) fn each_addr<A, F, T>(addr: A, mut f: F) -> Result<T, io::Error>
+where
+    A: ToSocketAddrs,
+    F: FnMut(SocketAddr) -> Result<T, io::Error>,
+{
+    let mut last_error = None;
+
+    for addr in addr.to_socket_addrs()? {
+        match f(addr) {
+            Ok(value) => return Ok(value),
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| io::ErrorKind::InvalidInput.into()))
+}
@@ -0,0 +1,156 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::BitOr;
+
+use super::Ipv6Address;
+use crate::io;
+use crate::sys;
+
+/// A network interface, obtained from [interfaces].
+///
+/// Multicast membership, binding to an interface and the EUI lookup all take an interface.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Interface(pub(crate) sys::Interface);
+
+impl Interface {
+    /// The identifier of the interface.
+    ///
+    /// On RIOT it is the PID of the interface's thread, other OS use their interface index.
+    pub fn index(&self) -> u32 {
+        self.0.index()
+    }
+
+    /// The name of the interface, RIOT names interfaces by their index.
+    pub fn name(&self) -> Result<String, io::Error> {
+        self.0.name()
+    }
+
+    /// The largest IPv6 packet the interface sends.
+    pub fn mtu(&self) -> Result<u32, io::Error> {
+        self.0.mtu()
+    }
+
+    /// The link-layer address, e.g. a MAC address. It is empty if the interface has none.
+    pub fn link_address(&self) -> Result<Vec<u8>, io::Error> {
+        self.0.link_address()
+    }
+
+    /// The unicast IPv6 addresses assigned to the interface.
+    pub fn ipv6_addrs(&self) -> Result<Vec<InterfaceAddr>, io::Error> {
+        self.0.ipv6_addrs()
+    }
+
+    /// Returns `true` if the interface is administratively up.
+    pub fn is_up(&self) -> Result<bool, io::Error> {
+        self.0.is_up()
+    }
+
+    /// Assigns `addr` to the interface, with an on-link prefix of `prefix_len` bits.
+    ///
+    /// With [Ipv6AddrFlags::TENTATIVE] duplicate address detection runs before the address is
+    /// used, otherwise it is valid right away. An address cannot be added [DEPRECATED], and
+    /// [ANYCAST] is only supported by RIOT. Linux requires `CAP_NET_ADMIN`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use os_glue::net::{self, Ipv6AddrFlags, Ipv6Address};
+    ///
+    /// let interface = net::interfaces().unwrap()[0];
+    /// let addr = Ipv6Address::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+    ///
+    /// interface.add_ipv6_addr(addr, 64, Ipv6AddrFlags::TENTATIVE).unwrap();
+    /// ```
+    ///
+    /// [DEPRECATED]: Ipv6AddrFlags::DEPRECATED
+    /// [ANYCAST]: Ipv6AddrFlags::ANYCAST
+    pub fn add_ipv6_addr<A>(
+        &self,
+        addr: A,
+        prefix_len: u8,
+        flags: Ipv6AddrFlags,
+    ) -> Result<(), io::Error>
+    where
+        A: Into<Ipv6Address>,
+    {
+        if prefix_len > 128 || flags.contains(Ipv6AddrFlags::DEPRECATED) {
+            return Err(io::ErrorKind::InvalidInput.into());
+        }
+        self.0.add_ipv6_addr(&addr.into(), prefix_len, flags)
+    }
+
+    /// Removes an address assigned to the interface.
+    pub fn remove_ipv6_addr<A>(&self, addr: A) -> Result<(), io::Error>
+    where
+        A: Into<Ipv6Address>,
+    {
+        self.0.remove_ipv6_addr(&addr.into())
+    }
+}
+
+/// An IPv6 address assigned to an [Interface].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct InterfaceAddr {
+    pub addr: Ipv6Address,
+    pub scope: Ipv6Scope,
+    pub flags: Ipv6AddrFlags,
+}
+
+/// The scope an IPv6 address is valid in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Ipv6Scope {
+    /// The loopback address.
+    Host,
+    /// `fe80::/10`
+    Link,
+    /// The deprecated `fec0::/10`.
+    Site,
+    Global,
+}
+
+/// The state of an [InterfaceAddr].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Ipv6AddrFlags(u8);
+
+impl Ipv6AddrFlags {
+    /// Duplicate address detection has not finished, the address is not used yet.
+    pub const TENTATIVE: Ipv6AddrFlags = Ipv6AddrFlags(1 << 0);
+    /// The preferred lifetime expired, the address is not used for new connections.
+    pub const DEPRECATED: Ipv6AddrFlags = Ipv6AddrFlags(1 << 1);
+    /// The address is an anycast address, only supported by RIOT.
+    pub const ANYCAST: Ipv6AddrFlags = Ipv6AddrFlags(1 << 2);
+
+    /// No flags set.
+    pub const fn empty() -> Ipv6AddrFlags {
+        Ipv6AddrFlags(0)
+    }
+
+    /// Returns `true` if all of the flags in `other` are set.
+    pub fn contains(self, other: Ipv6AddrFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Ipv6AddrFlags {
+    type Output = Ipv6AddrFlags;
+    fn bitor(self, other: Ipv6AddrFlags) -> Self::Output {
+        Ipv6AddrFlags(self.0 | other.0)
+    }
+}
+
+/// Returns the network interfaces of the node.
+///
+/// # Examples
+/// ```
+/// use os_glue::net;
+///
+/// for interface in net::interfaces().unwrap() {
+///     println!("{}: up {}", interface.name().unwrap(), interface.is_up().unwrap());
+///
+///     for addr in interface.ipv6_addrs().unwrap() {
+///         println!("    {} {:?}", addr.addr, addr.scope);
+///     }
+/// }
+/// ```
+pub fn interfaces() -> Result<Vec<Interface>, io::Error> {
+    Ok(sys::interfaces()?.into_iter().map(Interface).collect())
+}
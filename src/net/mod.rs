@@ -0,0 +1,377 @@
+use core::future::Future;
+use core::ops::Deref;
+use core::pin::Pin;
+use core::task::{LocalWaker, Poll};
+
+use crate::io;
+use crate::sys;
+
+pub use crate::sys::{IpAddress, Ipv4Address, Ipv6Address, SocketAddr};
+
+mod addr;
+mod interface;
+mod poll;
+mod recv_flags;
+
+pub use self::addr::*;
+pub use self::interface::*;
+pub use self::poll::*;
+pub use self::recv_flags::*;
+
+pub struct UdpSocket(sys::UdpSocket);
+
+/// A datagram borrowed from a socket by [UdpSocket::recv_buf].
+///
+/// On RIOT the datagram stays in the packet buffer of the network stack until this is dropped.
+pub struct RecvBuf<'a>(sys::RecvBuf<'a>);
+
+#[cfg(not(target_os = "riot"))]
+pub const IPV6_LOOPBACK: Ipv6Address = Ipv6Address::LOCALHOST;
+
+#[cfg(not(target_os = "riot"))]
+pub const IPV6_UNSPECIFIED: Ipv6Address = Ipv6Address::UNSPECIFIED;
+
+#[cfg(target_os = "riot")]
+pub const IPV6_LINK_LOCAL_ALL_ROUTERS: Ipv6Address = Ipv6Address::LINK_LOCAL_ALL_ROUTERS;
+
+#[cfg(target_os = "riot")]
+pub const IPV6_LOOPBACK: Ipv6Address = Ipv6Address::LOOPBACK;
+
+#[cfg(target_os = "riot")]
+pub const IPV6_UNSPECIFIED: Ipv6Address = Ipv6Address::UNSPECIFIED;
+
+impl UdpSocket {
+    /// Binds to the first of the addresses which succeeds.
+    ///
+    /// Port 0 requests an ephemeral port, see [local_addr](UdpSocket::local_addr).
+    pub fn bind<A>(addr: A) -> Result<UdpSocket, io::Error>
+    where
+        A: ToSocketAddrs,
+    {
+        self::addr::each_addr(addr, sys::UdpSocket::bind).map(UdpSocket)
+    }
+
+    /// Binds to the first of the addresses which succeeds, receiving and sending only through
+    /// `interface`.
+    ///
+    /// On RIOT this sets the `netif` of the local endpoint, other OS use `SO_BINDTODEVICE`,
+    /// which may require privileges.
+    pub fn bind_on<A>(addr: A, interface: &Interface) -> Result<UdpSocket, io::Error>
+    where
+        A: ToSocketAddrs,
+    {
+        self::addr::each_addr(addr, |addr| sys::UdpSocket::bind_on(addr, &interface.0))
+            .map(UdpSocket)
+    }
+
+    /// Returns the address the socket is bound to.
+    pub fn local_addr(&self) -> Result<sys::SocketAddr, io::Error> {
+        self.0.local_addr()
+    }
+
+    /// Receives a datagram into `buf`.
+    ///
+    /// On RIOT a datagram larger than `buf` is dropped with `BufferToSmall`, other OS truncate
+    /// it silently. Use [recv_from_with_flags] to detect truncation on every OS.
+    ///
+    /// [recv_from_with_flags]: UdpSocket::recv_from_with_flags
+    pub fn recv_from(&mut self, buf: &mut [u8]) -> Result<(usize, sys::SocketAddr), io::Error> {
+        self.0.recv_from(buf)
+    }
+
+    /// Receives a datagram without copying it into a caller buffer.
+    ///
+    /// RIOT hands out the datagram in the packet buffer of the network stack, saving the copy.
+    /// Other OS receive it into a buffer of the socket, which is reused by the next call.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use os_glue::net::{UdpSocket, IPV6_UNSPECIFIED};
+    ///
+    /// let mut socket = UdpSocket::bind((IPV6_UNSPECIFIED, 5683)).unwrap();
+    ///
+    /// let datagram = socket.recv_buf().unwrap();
+    /// println!("{} bytes from {}", datagram.len(), datagram.addr());
+    /// ```
+    pub fn recv_buf(&mut self) -> Result<RecvBuf, io::Error> {
+        self.0.recv_buf().map(RecvBuf)
+    }
+
+    /// Receives a datagram into `buf` without removing it from the queue.
+    ///
+    /// A datagram larger than `buf` is truncated.
+    pub fn peek_from(&mut self, buf: &mut [u8]) -> Result<(usize, sys::SocketAddr), io::Error> {
+        let info = self.recv_from_with_flags(buf, RecvFlags::PEEK)?;
+        Ok((info.len, info.addr))
+    }
+
+    /// Receives a datagram into `buf`, truncating it if it is larger, and reports its length.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use os_glue::net::{RecvFlags, UdpSocket, IPV6_UNSPECIFIED};
+    ///
+    /// let mut socket = UdpSocket::bind((IPV6_UNSPECIFIED, 5683)).unwrap();
+    /// let mut buf = [0; 64];
+    ///
+    /// let info = socket.recv_from_with_flags(&mut buf, RecvFlags::empty()).unwrap();
+    /// if info.is_truncated() {
+    ///     println!("dropped {} bytes", info.datagram_len - info.len);
+    /// }
+    /// ```
+    pub fn recv_from_with_flags(
+        &mut self,
+        buf: &mut [u8],
+        flags: RecvFlags,
+    ) -> Result<RecvInfo, io::Error> {
+        self.0.recv_from_with_flags(buf, flags)
+    }
+
+    /// Receives a datagram from the connected peer.
+    pub fn recv(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        self.0.recv(buf)
+    }
+
+    pub fn send_to<A>(&mut self, buf: &[u8], addr: A) -> Result<usize, io::Error>
+    where
+        A: Into<sys::SocketAddr>,
+    {
+        self.0.send_to(buf, addr.into())
+    }
+
+    /// Sends a datagram to the connected peer.
+    pub fn send(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        self.0.send(buf)
+    }
+
+    /// Sets the peer of [send](UdpSocket::send) and [recv](UdpSocket::recv). Datagrams from
+    /// other sources are dropped.
+    ///
+    /// On RIOT the socket is re-created, losing datagrams which were not read yet, as well as
+    /// its registration with a [Poll].
+    pub fn connect<A>(&mut self, addr: A) -> Result<(), io::Error>
+    where
+        A: Into<sys::SocketAddr>,
+    {
+        self.0.connect(addr.into())
+    }
+
+    /// Returns the address of the connected peer.
+    pub fn peer_addr(&self) -> Result<sys::SocketAddr, io::Error> {
+        self.0.peer_addr()
+    }
+
+    /// Joins an IPv6 multicast group on `interface`.
+    ///
+    /// On RIOT the membership belongs to the interface rather than the socket, so it outlives
+    /// the socket.
+    pub fn join_multicast<A>(
+        &mut self,
+        multiaddr: A,
+        interface: &Interface,
+    ) -> Result<(), io::Error>
+    where
+        A: Into<sys::Ipv6Address>,
+    {
+        self.0.join_multicast_v6(&multiaddr.into(), &interface.0)
+    }
+
+    pub fn leave_multicast<A>(
+        &mut self,
+        multiaddr: A,
+        interface: &Interface,
+    ) -> Result<(), io::Error>
+    where
+        A: Into<sys::Ipv6Address>,
+    {
+        self.0.leave_multicast_v6(&multiaddr.into(), &interface.0)
+    }
+
+    /// Joins an IPv4 multicast group on `interface`.
+    ///
+    /// RIOT has no IPv4 support and returns `NotSupported`.
+    pub fn join_multicast_v4<A>(
+        &mut self,
+        multiaddr: A,
+        interface: &Interface,
+    ) -> Result<(), io::Error>
+    where
+        A: Into<sys::Ipv4Address>,
+    {
+        self.0.join_multicast_v4(&multiaddr.into(), &interface.0)
+    }
+
+    /// Leaves an IPv4 multicast group, see [join_multicast_v4](UdpSocket::join_multicast_v4).
+    pub fn leave_multicast_v4<A>(
+        &mut self,
+        multiaddr: A,
+        interface: &Interface,
+    ) -> Result<(), io::Error>
+    where
+        A: Into<sys::Ipv4Address>,
+    {
+        self.0.leave_multicast_v4(&multiaddr.into(), &interface.0)
+    }
+
+    /// Allows sending to IPv4 broadcast addresses.
+    ///
+    /// RIOT has no IPv4 support and returns `NotSupported`.
+    pub fn set_broadcast(&mut self, broadcast: bool) -> Result<(), io::Error> {
+        self.0.set_broadcast(broadcast)
+    }
+
+    /// Whether multicast datagrams sent by the socket are looped back to the host.
+    ///
+    /// RIOT never loops them back and returns `NotSupported` when enabling it.
+    pub fn set_multicast_loop(&mut self, multicast_loop: bool) -> Result<(), io::Error> {
+        self.0.set_multicast_loop(multicast_loop)
+    }
+
+    /// Sets the hop limit, or TTL for IPv4, of multicast datagrams sent by the socket.
+    ///
+    /// GNRC has no hop limit per socket, so RIOT returns `NotSupported`.
+    pub fn set_multicast_hops(&mut self, hops: u8) -> Result<(), io::Error> {
+        self.0.set_multicast_hops(hops)
+    }
+
+    pub fn set_nonblocking(&mut self, nonblocking: bool) -> Result<(), io::Error> {
+        self.0.set_nonblocking(nonblocking)
+    }
+}
+
+/// A UDP socket for use with `async`/`await`.
+///
+/// The futures are woken by the network stack on RIOT and by a reactor thread on other OS.
+///
+/// # Examples
+/// ```no_run
+/// #![feature(async_await, await_macro, futures_api)]
+///
+/// use os_glue::net::{AsyncUdpSocket, IPV6_UNSPECIFIED};
+/// use os_glue::task;
+///
+/// task::block_on(async {
+///     let mut socket = AsyncUdpSocket::bind((IPV6_UNSPECIFIED, 5683)).unwrap();
+///     let mut buf = [0; 128];
+///
+///     loop {
+///         let (len, peer) = await!(socket.recv_from(&mut buf)).unwrap();
+///         await!(socket.send_to(&buf[..len], peer)).unwrap();
+///     }
+/// });
+/// ```
+pub struct AsyncUdpSocket(sys::AsyncUdpSocket);
+
+/// Future returned by [AsyncUdpSocket::recv_from].
+pub struct RecvFrom<'a> {
+    socket: &'a mut AsyncUdpSocket,
+    buf: &'a mut [u8],
+}
+
+/// Future returned by [AsyncUdpSocket::send_to].
+pub struct SendTo<'a> {
+    socket: &'a mut AsyncUdpSocket,
+    buf: &'a [u8],
+    addr: sys::SocketAddr,
+}
+
+impl AsyncUdpSocket {
+    pub fn bind<A>(addr: A) -> Result<AsyncUdpSocket, io::Error>
+    where
+        A: ToSocketAddrs,
+    {
+        self::addr::each_addr(addr, sys::AsyncUdpSocket::bind).map(AsyncUdpSocket)
+    }
+
+    /// Returns the address the socket is bound to.
+    pub fn local_addr(&self) -> Result<sys::SocketAddr, io::Error> {
+        self.0.local_addr()
+    }
+
+    /// Receives a datagram, completing once one arrived.
+    pub fn recv_from<'a>(&'a mut self, buf: &'a mut [u8]) -> RecvFrom<'a> {
+        RecvFrom { socket: self, buf }
+    }
+
+    /// Sends a datagram, completing once it was handed to the network stack.
+    pub fn send_to<'a, A>(&'a mut self, buf: &'a [u8], addr: A) -> SendTo<'a>
+    where
+        A: Into<sys::SocketAddr>,
+    {
+        SendTo {
+            socket: self,
+            buf,
+            addr: addr.into(),
+        }
+    }
+}
+
+impl<'a> Future for RecvFrom<'a> {
+    type Output = Result<(usize, sys::SocketAddr), io::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.socket.0.poll_recv_from(lw, this.buf)
+    }
+}
+
+impl<'a> Future for SendTo<'a> {
+    type Output = Result<usize, io::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.socket.0.poll_send_to(lw, this.buf, this.addr)
+    }
+}
+
+impl<'a> RecvBuf<'a> {
+    /// The sender of the datagram.
+    pub fn addr(&self) -> sys::SocketAddr {
+        self.0.addr()
+    }
+}
+
+impl<'a> Deref for RecvBuf<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+pub struct Eui64(pub [u8; 8]);
+
+pub struct Eui48(pub [u8; 6]);
+
+/// Returns the modified EUI-64 of `interface`, as used by IPv6 interface identifiers.
+///
+/// On RIOT the device reports it, other OS derive it from the MAC address. Interfaces
+/// without one, e.g. loopback, return `AddrNotAvailable`.
+///
+/// # Examples
+/// ```no_run
+/// use os_glue::net;
+///
+/// for interface in net::interfaces().unwrap() {
+///     if let Ok(eui) = net::eui64(&interface) {
+///         println!("{:x?}", eui.0);
+///     }
+/// }
+/// ```
+pub fn eui64(interface: &Interface) -> Result<Eui64, io::Error> {
+    sys::eui64(&interface.0)
+}
+
+/// Returns the MAC address of `interface`.
+///
+/// Interfaces with another or no link-layer address, e.g. IEEE 802.15.4, return
+/// `AddrNotAvailable`.
+pub fn eui48(interface: &Interface) -> Result<Eui48, io::Error> {
+    sys::eui48(&interface.0)
+}
+
+/// Alias of [eui48].
+#[inline]
+pub fn mac_address(interface: &Interface) -> Result<Eui48, io::Error> {
+    eui48(interface)
+}
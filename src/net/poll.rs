@@ -0,0 +1,182 @@
+use alloc::vec::Vec;
+use core::ops::BitOr;
+use core::slice;
+
+use super::UdpSocket;
+use crate::io;
+use crate::sys;
+use crate::time::Duration;
+
+/// Identifies a registered socket in the [Event]s of a [Poll].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Token(pub usize);
+
+/// The readiness a socket is registered for.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Interest(u8);
+
+impl Interest {
+    pub const READABLE: Interest = Interest(1);
+    pub const WRITABLE: Interest = Interest(2);
+
+    #[inline]
+    pub fn is_readable(self) -> bool {
+        self.0 & Interest::READABLE.0 != 0
+    }
+
+    #[inline]
+    pub fn is_writable(self) -> bool {
+        self.0 & Interest::WRITABLE.0 != 0
+    }
+}
+
+impl BitOr for Interest {
+    type Output = Interest;
+
+    fn bitor(self, other: Interest) -> Interest {
+        Interest(self.0 | other.0)
+    }
+}
+
+/// Waits for readiness of several sockets at once.
+///
+/// Readiness is level-triggered: a socket is reported again as long as it has datagrams
+/// pending. Sockets should be nonblocking, as readiness may be reported spuriously.
+///
+/// On RIOT the poll must be used by the thread which created it. Dropping a socket deregisters
+/// it, dropping the poll ends the registrations of all its sockets.
+///
+/// # Examples
+/// ```no_run
+/// use os_glue::net::{Events, Interest, Poll, Token, UdpSocket, IPV6_UNSPECIFIED};
+/// use os_glue::time::Duration;
+///
+/// let mut coap = UdpSocket::bind((IPV6_UNSPECIFIED, 5683)).unwrap();
+/// let mut mdns = UdpSocket::bind((IPV6_UNSPECIFIED, 5353)).unwrap();
+///
+/// let mut poll = Poll::new().unwrap();
+/// poll.register(&mut coap, Token(0), Interest::READABLE).unwrap();
+/// poll.register(&mut mdns, Token(1), Interest::READABLE).unwrap();
+///
+/// let mut events = Events::with_capacity(4);
+/// let mut buf = [0; 128];
+///
+/// loop {
+///     poll.poll(&mut events, Some(Duration::from_secs(1))).unwrap();
+///
+///     for event in events.iter() {
+///         let socket = match event.token() {
+///             Token(0) => &mut coap,
+///             _ => &mut mdns,
+///         };
+///         let (len, peer) = socket.recv_from(&mut buf).unwrap();
+///         socket.send_to(&buf[..len], peer).unwrap();
+///     }
+/// }
+/// ```
+pub struct Poll(sys::Poll);
+
+/// Readiness of a registered socket.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Event {
+    token: Token,
+    readiness: Interest,
+}
+
+/// Buffer for the [Event]s reported by [Poll::poll].
+pub struct Events {
+    inner: Vec<Event>,
+    capacity: usize,
+}
+
+impl Poll {
+    pub fn new() -> Result<Poll, io::Error> {
+        Ok(Poll(sys::Poll::new()?))
+    }
+
+    /// Reports readiness of `socket` for `interest` as events carrying `token`.
+    pub fn register(
+        &mut self,
+        socket: &mut UdpSocket,
+        token: Token,
+        interest: Interest,
+    ) -> Result<(), io::Error> {
+        self.0.register(&mut socket.0, token, interest)
+    }
+
+    /// Changes the token and interest of a registered socket.
+    pub fn reregister(
+        &mut self,
+        socket: &mut UdpSocket,
+        token: Token,
+        interest: Interest,
+    ) -> Result<(), io::Error> {
+        self.0.reregister(&mut socket.0, token, interest)
+    }
+
+    pub fn deregister(&mut self, socket: &mut UdpSocket) -> Result<(), io::Error> {
+        self.0.deregister(&mut socket.0)
+    }
+
+    /// Blocks until a registered socket is ready or the timeout elapsed, filling `events`.
+    ///
+    /// Without timeout it blocks indefinitely. On timeout `events` is empty.
+    pub fn poll(
+        &mut self,
+        events: &mut Events,
+        timeout: Option<Duration>,
+    ) -> Result<(), io::Error> {
+        events.inner.clear();
+        self.0.poll(&mut events.inner, events.capacity, timeout)
+    }
+}
+
+impl Event {
+    pub(crate) fn new(token: Token, readiness: Interest) -> Event {
+        Event { token, readiness }
+    }
+
+    #[inline]
+    pub fn token(&self) -> Token {
+        self.token
+    }
+
+    /// Errors are reported as readable and writable, so they surface on the next operation.
+    #[inline]
+    pub fn is_readable(&self) -> bool {
+        self.readiness.is_readable()
+    }
+
+    #[inline]
+    pub fn is_writable(&self) -> bool {
+        self.readiness.is_writable()
+    }
+}
+
+impl Events {
+    /// Holds up to `capacity` events per poll.
+    pub fn with_capacity(capacity: usize) -> Events {
+        Events {
+            inner: Vec::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn iter(&self) -> slice::Iter<Event> {
+        self.inner.iter()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Events {
+    type Item = &'a Event;
+    type IntoIter = slice::Iter<'a, Event>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter()
+    }
+}
@@ -0,0 +1,51 @@
+use core::ops::BitOr;
+
+use super::SocketAddr;
+
+/// Options for receiving a datagram with [`UdpSocket::recv_from_with_flags`].
+///
+/// [`UdpSocket::recv_from_with_flags`]: struct.UdpSocket.html#method.recv_from_with_flags
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct RecvFlags(u8);
+
+impl RecvFlags {
+    /// Leave the datagram queued, so that the next receive returns it again.
+    pub const PEEK: RecvFlags = RecvFlags(1 << 0);
+
+    /// No flags set.
+    pub const fn empty() -> RecvFlags {
+        RecvFlags(0)
+    }
+
+    /// Returns `true` if all of the flags in `other` are set.
+    pub fn contains(self, other: RecvFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for RecvFlags {
+    type Output = RecvFlags;
+    fn bitor(self, other: RecvFlags) -> Self::Output {
+        RecvFlags(self.0 | other.0)
+    }
+}
+
+/// A datagram received with [`UdpSocket::recv_from_with_flags`].
+///
+/// [`UdpSocket::recv_from_with_flags`]: struct.UdpSocket.html#method.recv_from_with_flags
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RecvInfo {
+    /// Bytes copied into the buffer.
+    pub len: usize,
+    /// Length of the whole datagram, larger than `len` if it was truncated.
+    pub datagram_len: usize,
+    /// The sender.
+    pub addr: SocketAddr,
+}
+
+impl RecvInfo {
+    /// Returns `true` if the datagram did not fit into the buffer.
+    pub fn is_truncated(&self) -> bool {
+        self.datagram_len > self.len
+    }
+}
@@ -0,0 +1,95 @@
+use alloc::vec::Vec;
+
+use crate::sync::Mutex;
+use crate::sys;
+use crate::thread::{self, Thread};
+
+/// Thread flag used to wake threads blocked on a [Barrier].
+const BARRIER_FLAG: thread::Flags = 1 << 13;
+
+/// A barrier enables multiple threads to synchronize the beginning of some computation.
+///
+/// Blocked threads sleep on a thread flag and are woken by the last thread reaching the barrier.
+pub struct Barrier {
+    state: Mutex<BarrierState>,
+    num_threads: usize,
+}
+
+struct BarrierState {
+    count: usize,
+    generation: usize,
+    waiting: Vec<Thread>,
+}
+
+/// A `BarrierWaitResult` is returned by [wait] when all threads in the [Barrier] have rendezvoused.
+///
+/// [wait]: Barrier::wait
+#[derive(Debug)]
+pub struct BarrierWaitResult(bool);
+
+impl Barrier {
+    /// Creates a new barrier that can block a given number of threads.
+    ///
+    /// A barrier will block `n-1` threads which call [wait] and then wake up all threads at once
+    /// when the `n`th thread calls [wait].
+    ///
+    /// [wait]: Barrier::wait
+    pub fn new(n: usize) -> Barrier {
+        Barrier {
+            state: Mutex::new(BarrierState {
+                count: 0,
+                generation: 0,
+                waiting: Vec::with_capacity(n),
+            }),
+            num_threads: n,
+        }
+    }
+
+    /// Blocks the current thread until all threads have rendezvoused here.
+    ///
+    /// A single (arbitrary) thread will receive a [BarrierWaitResult] that returns `true` from
+    /// [is_leader] when returning from this function, and all other threads will receive a result
+    /// that will return `false` from [is_leader].
+    ///
+    /// [is_leader]: BarrierWaitResult::is_leader
+    pub fn wait(&self) -> BarrierWaitResult {
+        let mut state = self.state.lock();
+        let generation = state.generation;
+        state.count += 1;
+
+        if state.count < self.num_threads {
+            state.waiting.push(thread::current());
+            drop(state);
+
+            // Other users of the flag may wake us up early, so check whether the barrier moved on.
+            loop {
+                sys::wait_flags_any(BARRIER_FLAG, None);
+                if self.state.lock().generation != generation {
+                    break;
+                }
+            }
+
+            BarrierWaitResult(false)
+        } else {
+            state.count = 0;
+            state.generation = state.generation.wrapping_add(1);
+            for waiting in state.waiting.drain(..) {
+                waiting.set_flags(BARRIER_FLAG);
+            }
+
+            BarrierWaitResult(true)
+        }
+    }
+}
+
+impl BarrierWaitResult {
+    /// Returns whether this thread from [wait] is the "leader thread".
+    ///
+    /// Only one thread will have `true` returned from their result, all other threads will have
+    /// `false` returned.
+    ///
+    /// [wait]: Barrier::wait
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
@@ -5,5 +5,24 @@ mod mutex;
 #[cfg(target_os = "riot")]
 pub use self::mutex::*;
 
+// Built on thread flags, which are emulated on other OS.
+mod barrier;
+
+pub use self::barrier::*;
+
 #[cfg(feature = "std")]
 pub use spin::{Mutex, MutexGuard};
+
+// The standard library does not expose its reentrant mutex, so provide one for every OS.
+mod remutex;
+
+pub use self::remutex::*;
+
+/// An enumeration of possible erros which can occur while trying to acquire a lock, from the
+/// [try_lock] method on a [Mutex] or [ReentrantMutex].
+///
+/// [try_lock]: ReentrantMutex::try_lock
+pub enum TryLock {
+    /// The lock could not be acquired at this time because the operation would otherwise block.
+    WouldBlock,
+}
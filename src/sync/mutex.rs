@@ -4,6 +4,7 @@ use core::marker::PhantomData;
 use core::ops::Deref;
 use core::ops::DerefMut;
 
+use super::TryLock;
 use crate::sys;
 
 /// Mutual exclusion primitive.
@@ -15,15 +16,6 @@ pub struct Mutex<T: ?Sized> {
     data: UnsafeCell<T>,
 }
 
-/// An enumeration of possible erros which can occur while trying to acquire a lock, from the
-/// [try_lock] method on a [Mutex]
-///
-/// [try_lock]: Mutex::try_lock
-pub enum TryLock {
-    /// The lock could not be acquired at this time because the operation would otherwise block.
-    WouldBlock,
-}
-
 /// An RAII implmentation of a 'scoped lock' of a mutex. When this structure is dropped, the lock
 /// will be unlocked
 ///
@@ -0,0 +1,133 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use super::TryLock;
+use crate::sys;
+
+/// Reentrant mutual exclusion primitive.
+///
+/// The thread holding the lock may acquire it again without deadlocking. Because of this only
+/// shared access to the protected data is handed out, use a [Cell] or [RefCell] for mutation.
+///
+/// [Cell]: https://doc.rust-lang.org/core/cell/struct.Cell.html
+/// [RefCell]: https://doc.rust-lang.org/core/cell/struct.RefCell.html
+pub struct ReentrantMutex<T: ?Sized> {
+    lock: sys::ReentrantMutex,
+    data: T,
+}
+
+/// An RAII implementation of a 'scoped lock' of a reentrant mutex. When this structure is dropped,
+/// the lock will be released once.
+///
+/// The data can be accessed through this guard via its [Deref] implementation.
+///
+/// This structure is created by the [lock] or [try_lock] methods of a [ReentrantMutex].
+///
+/// [lock]: ReentrantMutex::lock
+/// [try_lock]: ReentrantMutex::try_lock
+/// [Deref]: https://doc.rust-lang.org/core/ops/trait.Deref.html
+pub struct ReentrantMutexGuard<'lock, T: ?Sized + 'lock> {
+    inner: &'lock ReentrantMutex<T>,
+    // The guard is not Send
+    _marker: PhantomData<*const T>,
+}
+
+impl<T> ReentrantMutex<T> {
+    /// Create a new unlocked reentrant mutex.
+    pub const fn new(data: T) -> Self {
+        ReentrantMutex {
+            lock: unsafe { sys::ReentrantMutex::new() },
+            data,
+        }
+    }
+}
+
+impl<T: ?Sized> ReentrantMutex<T> {
+    /// Acquire the mutex, blocking the current thread unless it already holds the lock.
+    pub fn lock(&self) -> ReentrantMutexGuard<T> {
+        unsafe {
+            self.lock.lock();
+        }
+        ReentrantMutexGuard::new(self)
+    }
+
+    /// Attempts to acquire this lock.
+    ///
+    /// If the lock is held by another thread, then [TryLock] is returned. Otherwise, an RAII guard
+    /// is returned.
+    pub fn try_lock(&self) -> Result<ReentrantMutexGuard<T>, TryLock> {
+        if unsafe { self.lock.try_lock() } {
+            Ok(ReentrantMutexGuard::new(self))
+        } else {
+            Err(TryLock::WouldBlock)
+        }
+    }
+}
+
+unsafe impl<T: ?Sized + Send> Sync for ReentrantMutex<T> {}
+unsafe impl<T: ?Sized + Send> Send for ReentrantMutex<T> {}
+
+impl<'lock, T: ?Sized> ReentrantMutexGuard<'lock, T> {
+    fn new(mutex: &'lock ReentrantMutex<T>) -> Self {
+        ReentrantMutexGuard {
+            inner: mutex,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'lock, T: ?Sized> Deref for ReentrantMutexGuard<'lock, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.inner.data
+    }
+}
+
+impl<'lock, T: ?Sized> Drop for ReentrantMutexGuard<'lock, T> {
+    fn drop(&mut self) {
+        unsafe {
+            self.inner.lock.unlock();
+        }
+    }
+}
+
+impl<T> From<T> for ReentrantMutex<T> {
+    fn from(data: T) -> Self {
+        ReentrantMutex::new(data)
+    }
+}
+
+impl<T: Default> Default for ReentrantMutex<T> {
+    fn default() -> Self {
+        ReentrantMutex::new(Default::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ReentrantMutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut builder = f.debug_struct("ReentrantMutex");
+
+        match self.try_lock() {
+            Ok(guard) => builder.field("data", &*guard).finish(),
+            Err(_) => {
+                struct LockedMutex;
+                impl fmt::Debug for LockedMutex {
+                    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "<locked>")
+                    }
+                }
+
+                builder.field("data", &LockedMutex).finish()
+            }
+        }
+    }
+}
+
+impl<'lock, T: fmt::Debug> fmt::Debug for ReentrantMutexGuard<'lock, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReentrantMutexGuard")
+            .field("lock", &**self)
+            .finish()
+    }
+}
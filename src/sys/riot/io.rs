@@ -1,14 +1,15 @@
 use core::fmt;
 use core::fmt::Write;
-use crate::sync::Mutex;
+use crate::sync::ReentrantMutex;
 use riot_sys::ffi;
 
 struct Writer;
-struct SyncWriter(Mutex<Writer>);
+// Reentrant, so that printing from within a `Debug` or `Display` impl cannot deadlock.
+struct SyncWriter(ReentrantMutex<Writer>);
 
-static WRITER: SyncWriter = SyncWriter(Mutex::new(Writer));
+static WRITER: SyncWriter = SyncWriter(ReentrantMutex::new(Writer));
 
-impl fmt::Write for Writer {
+impl<'a> fmt::Write for &'a Writer {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         unsafe {
             ffi::print(s.as_ptr(), s.len());
@@ -20,7 +21,8 @@ impl fmt::Write for Writer {
 #[inline(always)]
 #[doc(hidden)]
 pub fn _print(args: fmt::Arguments) {
-    let mut writer = WRITER.0.lock();
+    let guard = WRITER.0.lock();
+    let mut writer: &Writer = &guard;
     writer.write_fmt(args).unwrap()
 }
 
@@ -33,6 +35,9 @@ pub struct Error {
 pub enum ErrorKind {
     OutOfMemory,
     AddrInUse,
+    AddrNotAvailable,
+    /// Never returned any more, missing addresses are reported as on other OS.
+    #[deprecated(note = "replaced by `AddrNotAvailable`")]
     AddrMissing,
     BufferToSmall,
     AfNoSupport,
@@ -43,6 +48,7 @@ pub enum ErrorKind {
     Timeout,
     HostUnreachable,
     NoMatchingInterface,
+    NotConnected,
 }
 
 impl Error {
@@ -2,5 +2,7 @@
 pub mod io;
 pub mod mutex;
 pub mod net;
+pub mod remutex;
 pub mod thread;
+pub mod thread_local;
 pub mod time;
@@ -1,84 +1,261 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
 use core::mem;
+use core::ops::Deref;
 use core::ptr;
+use core::slice;
+use core::task::{LocalWaker, Poll, Waker};
 use crate::io::{self, Error, ErrorKind};
 use crate::net;
+use crate::sync::Mutex;
+use crate::time::Duration;
 use riot_sys::ffi;
 
+use super::thread::duration_to_micros64;
+
 pub use smoltcp::wire::{IpAddress, IpEndpoint as SocketAddr, Ipv4Address, Ipv6Address};
 
 pub struct UdpSocket {
-    inner: ffi::sock_udp_t,
+    // Boxed, RIOT keeps pointers to the sock until it is closed.
+    inner: Box<ffi::sock_udp_t>,
+    // Whether `inner` was created and not yet closed.
+    open: bool,
+    // Set while registered with a `Poll`, ended when the sock is closed.
+    registration: Option<Arc<Mutex<Registration>>>,
+    // GNRC cannot peek, so a peeked datagram is kept until it is received.
+    peeked: Option<(Vec<u8>, SocketAddr)>,
+}
+
+/// A datagram in GNRC's packet buffer, released on drop.
+pub struct RecvBuf<'a> {
+    sock: &'a mut ffi::sock_udp_t,
+    data: RecvData,
+    addr: SocketAddr,
+}
+
+enum RecvData {
+    Packet {
+        data: *const u8,
+        len: usize,
+        buf_ctx: *mut ffi::c_void,
+    },
+    // A peeked datagram, or a payload GNRC split into several snips.
+    Copied(Vec<u8>),
 }
 
 impl UdpSocket {
     #[inline]
     pub fn bind(local: SocketAddr) -> Result<Self, io::Error> {
-        // Will be set initialized by sock_udp_create
-        let mut sock_udp = unsafe { mem::zeroed() };
+        let mut socket = UdpSocket::unbound();
+
+        let local = UdpSocket::raw_endpoint(&local);
 
         // Do not configure a remote
-        let remote = ptr::null_mut();
+        socket.create(&local, None)?;
 
-        let local = UdpSocket::raw_endpoint(&local);
+        Ok(socket)
+    }
 
-        let error = unsafe { ffi::sock_udp_create(&mut sock_udp, &local, remote, 0) };
+    /// Binds to `local` on `interface` only.
+    pub fn bind_on(local: SocketAddr, interface: &Interface) -> Result<Self, io::Error> {
+        let mut socket = UdpSocket::unbound();
 
-        match error {
-            error if error == -(ffi::EADDRINUSE as i32) => Err(ErrorKind::AddrInUse.into()),
-            error if error == -(ffi::EAFNOSUPPORT as i32) => Err(ErrorKind::AfNoSupport.into()),
-            error if error == -(ffi::EINVAL as i32) => Err(ErrorKind::InvalidInput.into()),
-            0 => {
-                let inner = sock_udp;
-                Ok(UdpSocket { inner })
-            }
-            _ => unreachable!("Unknown error occured. RIOT API changed."),
+        let mut local = UdpSocket::raw_endpoint(&local);
+        local.netif = interface.pid as _;
+
+        socket.create(&local, None)?;
+
+        Ok(socket)
+    }
+
+    /// A socket without a sock, which is initialized by [create](UdpSocket::create).
+    fn unbound() -> UdpSocket {
+        UdpSocket {
+            inner: Box::new(unsafe { mem::zeroed() }),
+            open: false,
+            registration: None,
+            peeked: None,
         }
     }
 
+    /// Creates the sock in its box, RIOT keeps pointers to it until it is closed.
+    fn create(
+        &mut self,
+        local: &ffi::sock_udp_ep_t,
+        remote: Option<&ffi::sock_udp_ep_t>,
+    ) -> Result<(), io::Error> {
+        create(&mut *self.inner, local, remote)?;
+        self.open = true;
+        Ok(())
+    }
+
+    /// Sets the remote endpoint of `send` and drops datagrams from other sources.
+    ///
+    /// RIOT sets the remote on creation only, so the sock is re-created on the same local
+    /// endpoint. Datagrams received but not yet read are lost.
+    pub fn connect(&mut self, remote: SocketAddr) -> Result<(), io::Error> {
+        let mut local = unsafe { mem::zeroed() };
+        let error = unsafe { ffi::sock_udp_get_local(&mut *self.inner, &mut local) };
+        if error != 0 {
+            return Err(ErrorKind::AddrNotAvailable.into());
+        }
+
+        let remote = UdpSocket::raw_endpoint(&remote);
+
+        // A new sock on the same local endpoint would collide with this one, so it is closed
+        // first. If creating fails, the socket stays closed and is not closed again on drop.
+        self.close();
+        self.peeked = None;
+        self.create(&local, Some(&remote))
+    }
+
     #[inline]
     pub fn recv_from(&mut self, buf: &mut [u8]) -> Result<(usize, SocketAddr), io::Error> {
-        let mut remote = unsafe { mem::zeroed() };
-        let timeout = 0;
+        if let Some((data, _)) = self.peeked.as_ref() {
+            // Kept, so that it can be received with a larger buffer.
+            if data.len() > buf.len() {
+                return Err(ErrorKind::BufferToSmall.into());
+            }
+        }
+        if let Some((data, addr)) = self.take_peeked() {
+            buf[..data.len()].copy_from_slice(&data);
+            return Ok((data.len(), addr));
+        }
 
-        assert_eq!(timeout, 0, "UdpSocket::recv_from should never block.");
+        let mut remote = unsafe { mem::zeroed() };
 
-        let error = unsafe {
+        let size = recv_result(unsafe {
             ffi::sock_udp_recv(
-                &mut self.inner,
+                &mut *self.inner,
                 buf.as_mut_ptr() as _,
                 buf.len(),
-                timeout,
+                0,
                 &mut remote,
             )
-        };
+        })?;
 
-        let addr = match remote.family as _ {
-            ffi::AF_INET6 => {
-                // union access is unsafe
-                let ipv6 = unsafe { remote.addr.ipv6 };
-                Ipv6Address::from_bytes(&ipv6).into()
+        Ok((size, UdpSocket::endpoint(&remote)))
+    }
+
+    pub fn recv_from_with_flags(
+        &mut self,
+        buf: &mut [u8],
+        flags: net::RecvFlags,
+    ) -> Result<net::RecvInfo, io::Error> {
+        if flags.contains(net::RecvFlags::PEEK) {
+            if self.peeked.is_none() {
+                let peeked = {
+                    let datagram = self.recv_buf()?;
+                    (datagram.to_vec(), datagram.addr())
+                };
+                self.peeked = Some(peeked);
+                self.set_peeked(true);
             }
-            ffi::AF_INET => {
-                // union access is unsafe
-                let ipv4 = unsafe { remote.addr.ipv4 };
-                Ipv4Address::from_bytes(&ipv4).into()
+
+            let (data, addr) = self.peeked.as_ref().unwrap();
+            return Ok(copy_datagram(buf, data, *addr));
+        }
+
+        // Also takes a peeked datagram.
+        let datagram = self.recv_buf()?;
+        Ok(copy_datagram(buf, &datagram, datagram.addr()))
+    }
+
+    /// GNRC usually keeps the payload of a datagram in a single snip, which is handed out.
+    /// A payload split into several snips is copied.
+    pub fn recv_buf(&mut self) -> Result<RecvBuf, io::Error> {
+        if let Some((data, addr)) = self.take_peeked() {
+            return Ok(RecvBuf {
+                sock: &mut *self.inner,
+                data: RecvData::Copied(data),
+                addr,
+            });
+        }
+
+        let mut remote: ffi::sock_udp_ep_t = unsafe { mem::zeroed() };
+        let mut data = ptr::null_mut();
+        let mut buf_ctx = ptr::null_mut();
+
+        let len = recv_result(unsafe {
+            ffi::sock_udp_recv_buf(&mut *self.inner, &mut data, &mut buf_ctx, 0, &mut remote)
+        })?;
+
+        // `sock_udp_recv_buf` only hands out the first snip, the next call releases the datagram.
+        let data = match unsafe { copy_split_payload(buf_ctx as *const ffi::gnrc_pktsnip_t) } {
+            Some(copied) => {
+                release(&mut *self.inner, buf_ctx);
+                RecvData::Copied(copied)
             }
-            _ => panic!("Unknown AF family"),
+            None => RecvData::Packet {
+                data: data as *const u8,
+                len,
+                buf_ctx,
+            },
         };
 
-        let endpoint = SocketAddr::new(addr, remote.port);
+        Ok(RecvBuf {
+            sock: &mut *self.inner,
+            data,
+            addr: UdpSocket::endpoint(&remote),
+        })
+    }
 
-        match error {
-            error if error == -(ffi::EADDRNOTAVAIL as isize) => Err(ErrorKind::AddrMissing.into()),
-            error if error == -(ffi::EAGAIN as isize) => Err(ErrorKind::WouldBlock.into()),
-            error if error == -(ffi::EINVAL as isize) => Err(ErrorKind::InvalidInput.into()),
-            error if error == -(ffi::ENOBUFS as isize) => Err(ErrorKind::BufferToSmall.into()),
-            error if error == -(ffi::ENOMEM as isize) => Err(ErrorKind::OutOfMemory.into()),
-            error if error == -(ffi::EPROTO as isize) => Err(ErrorKind::Protocol.into()),
-            // A timeout would be a would block, as the timeout is always 0.
-            error if error == -(ffi::ETIMEDOUT as isize) => Err(ErrorKind::WouldBlock.into()),
-            size if size >= 0 => Ok((size as _, endpoint)),
-            _ => unreachable!("Unknown error occured. RIOT API changed."),
+    /// Takes the peeked datagram, the socket is then only readable with datagrams pending.
+    fn take_peeked(&mut self) -> Option<(Vec<u8>, SocketAddr)> {
+        let peeked = self.peeked.take();
+        if peeked.is_some() {
+            self.set_peeked(false);
+        }
+        peeked
+    }
+
+    /// A peeked datagram left the mailbox, so a registered poll has to be told about it.
+    fn set_peeked(&self, peeked: bool) {
+        if let Some(registration) = self.registration.as_ref() {
+            registration.lock().peeked = peeked;
+        }
+    }
+
+    /// Receives from the connected remote.
+    #[inline]
+    pub fn recv(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        if self.peeked.is_some() {
+            return self.recv_from(buf).map(|(size, _)| size);
+        }
+
+        recv_result(unsafe {
+            ffi::sock_udp_recv(
+                &mut *self.inner,
+                buf.as_mut_ptr() as _,
+                buf.len(),
+                0,
+                ptr::null_mut(),
+            )
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, io::Error> {
+        let mut local = unsafe { mem::zeroed() };
+        // Only reads the sock.
+        let sock = &*self.inner as *const ffi::sock_udp_t as *mut _;
+
+        match unsafe { ffi::sock_udp_get_local(sock, &mut local) } {
+            0 => Ok(UdpSocket::endpoint(&local)),
+            _ => Err(ErrorKind::AddrNotAvailable.into()),
+        }
+    }
+
+    pub fn peer_addr(&self) -> Result<SocketAddr, io::Error> {
+        let mut remote = unsafe { mem::zeroed() };
+        // Only reads the sock.
+        let sock = &*self.inner as *const ffi::sock_udp_t as *mut _;
+
+        match unsafe { ffi::sock_udp_get_remote(sock, &mut remote) } {
+            0 => Ok(UdpSocket::endpoint(&remote)),
+            _ => Err(ErrorKind::NotConnected.into()),
         }
     }
 
@@ -110,6 +287,25 @@ impl UdpSocket {
         }
     }
 
+    /// The inverse of [raw_endpoint](UdpSocket::raw_endpoint).
+    fn endpoint(raw: &ffi::sock_udp_ep_t) -> SocketAddr {
+        let addr = match raw.family as _ {
+            ffi::AF_INET6 => {
+                // union access is unsafe
+                let ipv6 = unsafe { raw.addr.ipv6 };
+                Ipv6Address::from_bytes(&ipv6).into()
+            }
+            ffi::AF_INET => {
+                // union access is unsafe
+                let ipv4 = unsafe { raw.addr.ipv4 };
+                Ipv4Address::from_bytes(&ipv4).into()
+            }
+            _ => panic!("Unknown AF family"),
+        };
+
+        SocketAddr::new(addr, raw.port)
+    }
+
     #[inline]
     pub fn send_to<A>(&mut self, buf: &[u8], addr: A) -> Result<usize, io::Error>
     where
@@ -118,35 +314,37 @@ impl UdpSocket {
         let endpoint = addr.into();
         let remote = UdpSocket::raw_endpoint(&endpoint);
 
-        let error =
-            unsafe { ffi::sock_udp_send(&mut self.inner, buf.as_ptr() as _, buf.len(), &remote) };
+        send_result(unsafe {
+            ffi::sock_udp_send(&mut *self.inner, buf.as_ptr() as _, buf.len(), &remote)
+        })
+    }
 
-        match error {
-            error if error == -(ffi::EADDRINUSE as isize) => Err(ErrorKind::AddrInUse.into()),
-            error if error == -(ffi::EAFNOSUPPORT as isize) => Err(ErrorKind::AfNoSupport.into()),
-            error if error == -(ffi::EHOSTUNREACH as isize) => {
-                Err(ErrorKind::HostUnreachable.into())
-            }
-            error if error == -(ffi::EINVAL as isize) => Err(ErrorKind::InvalidInput.into()),
-            error if error == -(ffi::ENOMEM as isize) => Err(ErrorKind::OutOfMemory.into()),
-            error if error == -(ffi::ENOTCONN as isize) => unreachable!("NULL cannot be passed"),
-            size if size >= 0 => Ok(size as _),
-            _ => unreachable!("Unknown error occurred. RIOT API changed."),
-        }
+    /// Sends to the connected remote.
+    #[inline]
+    pub fn send(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        send_result(unsafe {
+            ffi::sock_udp_send(&mut *self.inner, buf.as_ptr() as _, buf.len(), ptr::null())
+        })
     }
 
+    /// Closing twice does nothing.
     #[inline]
     pub fn close(&mut self) {
-        unsafe { ffi::sock_udp_close(&mut self.inner) }
+        if let Some(registration) = self.registration.take() {
+            registration.lock().end();
+        }
+        if mem::replace(&mut self.open, false) {
+            unsafe { ffi::sock_udp_close(&mut *self.inner) }
+        }
     }
 
     #[inline]
     pub fn join_multicast_v6(
         &mut self,
         multiaddr: &Ipv6Address,
-        interface: u32,
+        interface: &Interface,
     ) -> Result<(), Error> {
-        let interface = find_interface(interface).ok_or(ErrorKind::NoMatchingInterface)?;
+        let interface = interface.netif()?;
 
         let mut addr_buffer = [0; 16];
         addr_buffer.copy_from_slice(multiaddr.as_bytes());
@@ -167,9 +365,9 @@ impl UdpSocket {
     pub fn leave_multicast_v6(
         &mut self,
         multiaddr: &Ipv6Address,
-        interface: u32,
+        interface: &Interface,
     ) -> Result<(), io::Error> {
-        let interface = find_interface(interface).ok_or(ErrorKind::NoMatchingInterface)?;
+        let interface = interface.netif()?;
 
         let mut addr_buffer = [0; 16];
         addr_buffer.copy_from_slice(multiaddr.as_bytes());
@@ -184,6 +382,47 @@ impl UdpSocket {
         }
     }
 
+    /// GNRC has no IPv4 support.
+    #[inline]
+    pub fn join_multicast_v4(
+        &mut self,
+        _multiaddr: &Ipv4Address,
+        _interface: &Interface,
+    ) -> Result<(), io::Error> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// GNRC has no IPv4 support.
+    #[inline]
+    pub fn leave_multicast_v4(
+        &mut self,
+        _multiaddr: &Ipv4Address,
+        _interface: &Interface,
+    ) -> Result<(), io::Error> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// GNRC has no IPv4 support.
+    #[inline]
+    pub fn set_broadcast(&mut self, _broadcast: bool) -> Result<(), io::Error> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    /// GNRC never loops multicast datagrams back.
+    #[inline]
+    pub fn set_multicast_loop(&mut self, multicast_loop: bool) -> Result<(), io::Error> {
+        if multicast_loop {
+            return Err(ErrorKind::NotSupported.into());
+        }
+        Ok(())
+    }
+
+    /// GNRC has no hop limit per socket, only one per interface, which applies to every socket.
+    #[inline]
+    pub fn set_multicast_hops(&mut self, _hops: u8) -> Result<(), io::Error> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
     #[inline]
     pub fn set_nonblocking(&mut self, _nonblocking: bool) -> Result<(), io::Error> {
         // The timeout on recvs is should always be zero,
@@ -192,16 +431,408 @@ impl UdpSocket {
     }
 }
 
-fn find_interface(mut index: u32) -> Option<*mut ffi::gnrc_netif_t> {
-    let mut next = ptr::null();
-    while let Some(interface) = unsafe { ffi::gnrc_netif_iter(next) }.into() {
-        if index == 0 {
-            return Some(interface);
+/// UDP socket woken by `sock_async` callbacks of the network stack.
+pub struct AsyncUdpSocket {
+    socket: UdpSocket,
+    // Boxed, RIOT keeps a pointer to it for the callback.
+    recv_waker: Box<Mutex<Option<Waker>>>,
+}
+
+impl AsyncUdpSocket {
+    pub fn bind(local: SocketAddr) -> Result<Self, io::Error> {
+        let mut socket = UdpSocket::unbound();
+        socket.create(&UdpSocket::raw_endpoint(&local), None)?;
+
+        let recv_waker = Box::new(Mutex::new(None));
+
+        unsafe {
+            ffi::sock_udp_set_cb(
+                &mut *socket.inner,
+                Some(AsyncUdpSocket::ready),
+                &*recv_waker as *const Mutex<Option<Waker>> as *mut _,
+            );
+        }
+
+        Ok(AsyncUdpSocket { socket, recv_waker })
+    }
+
+    #[inline]
+    pub fn local_addr(&self) -> Result<SocketAddr, io::Error> {
+        self.socket.local_addr()
+    }
+
+    /// Called from the thread of the network stack.
+    unsafe extern "C" fn ready(
+        _sock: *mut ffi::sock_udp_t,
+        flags: ffi::sock_async_flags_t,
+        arg: *mut ffi::c_void,
+    ) {
+        if flags & ffi::SOCK_ASYNC_MSG_RECV as ffi::sock_async_flags_t == 0 {
+            return;
+        }
+
+        let recv_waker = &*(arg as *const Mutex<Option<Waker>>);
+        if let Some(waker) = recv_waker.lock().take() {
+            waker.wake();
+        }
+    }
+
+    pub fn poll_recv_from(
+        &mut self,
+        lw: &LocalWaker,
+        buf: &mut [u8],
+    ) -> Poll<Result<(usize, SocketAddr), io::Error>> {
+        // Registered before receiving, so a datagram arriving in between wakes the task.
+        *self.recv_waker.lock() = Some(lw.clone().into_waker());
+
+        match self.socket.recv_from(buf) {
+            Err(ref error) if error.kind() == ErrorKind::WouldBlock => Poll::Pending,
+            result => Poll::Ready(result),
+        }
+    }
+
+    /// Sending does not block on RIOT.
+    pub fn poll_send_to(
+        &mut self,
+        _lw: &LocalWaker,
+        buf: &[u8],
+        addr: SocketAddr,
+    ) -> Poll<Result<usize, io::Error>> {
+        Poll::Ready(self.socket.send_to(buf, addr))
+    }
+}
+
+/// Readiness polling with `sock_async` events posted to an event queue.
+pub struct Poll {
+    // Boxed, RIOT keeps pointers to the queue.
+    queue: Box<ffi::event_queue_t>,
+    registrations: Vec<Arc<Mutex<Registration>>>,
+}
+
+/// Shared by the poll and the socket, whichever ends it first closes the events of the sock.
+struct Registration {
+    // Null once the registration ended.
+    sock: *mut ffi::sock_udp_t,
+    token: net::Token,
+    interest: net::Interest,
+    // `sock_async` flags received since the last poll.
+    received: ffi::sock_async_flags_t,
+    // The socket holds a peeked datagram, which is no longer in the mailbox of the sock.
+    peeked: bool,
+}
+
+impl Registration {
+    fn is_readable(&self) -> bool {
+        let recv = ffi::SOCK_ASYNC_MSG_RECV as ffi::sock_async_flags_t;
+        self.interest.is_readable() && (self.received & recv != 0 || self.peeked)
+    }
+
+    fn end(&mut self) {
+        if !self.sock.is_null() {
+            // Also cancels the events of the sock still in the queue.
+            unsafe { ffi::sock_udp_event_close(self.sock) };
+            self.sock = ptr::null_mut();
         }
-        next = interface;
-        index -= 1;
     }
-    None
+}
+
+impl Poll {
+    /// The queue belongs to the calling thread, which is the only one allowed to wait on it.
+    pub fn new() -> Result<Poll, io::Error> {
+        let mut queue = Box::new(unsafe { mem::zeroed() });
+        unsafe { ffi::event_queue_init(&mut *queue) };
+
+        Ok(Poll {
+            queue,
+            registrations: Vec::new(),
+        })
+    }
+
+    pub fn register(
+        &mut self,
+        socket: &mut UdpSocket,
+        token: net::Token,
+        interest: net::Interest,
+    ) -> Result<(), io::Error> {
+        if socket.registration.is_some() {
+            return Err(ErrorKind::AddrInUse.into());
+        }
+        if !socket.open {
+            return Err(ErrorKind::InvalidInput.into());
+        }
+
+        let sock = &mut *socket.inner as *mut ffi::sock_udp_t;
+        let registration = Arc::new(Mutex::new(Registration {
+            sock,
+            token,
+            interest,
+            received: 0,
+            peeked: socket.peeked.is_some(),
+        }));
+
+        unsafe {
+            ffi::sock_udp_event_init(
+                sock,
+                &mut *self.queue,
+                Some(Poll::ready),
+                &*registration as *const Mutex<Registration> as *mut _,
+            );
+        }
+
+        socket.registration = Some(registration.clone());
+        self.registrations.push(registration);
+        Ok(())
+    }
+
+    pub fn reregister(
+        &mut self,
+        socket: &mut UdpSocket,
+        token: net::Token,
+        interest: net::Interest,
+    ) -> Result<(), io::Error> {
+        let index = self.position(socket).ok_or(ErrorKind::InvalidInput)?;
+
+        let mut registration = self.registrations[index].lock();
+        registration.token = token;
+        registration.interest = interest;
+        Ok(())
+    }
+
+    pub fn deregister(&mut self, socket: &mut UdpSocket) -> Result<(), io::Error> {
+        let index = self.position(socket).ok_or(ErrorKind::InvalidInput)?;
+
+        self.registrations.swap_remove(index).lock().end();
+        socket.registration = None;
+        Ok(())
+    }
+
+    fn position(&self, socket: &UdpSocket) -> Option<usize> {
+        let registration = socket.registration.as_ref()?;
+        self.registrations
+            .iter()
+            .position(|registered| Arc::ptr_eq(registered, registration))
+    }
+
+    /// Runs in the polling thread, when the event is taken off the queue.
+    unsafe extern "C" fn ready(
+        _sock: *mut ffi::sock_udp_t,
+        flags: ffi::sock_async_flags_t,
+        arg: *mut ffi::c_void,
+    ) {
+        let registration = &*(arg as *const Mutex<Registration>);
+        registration.lock().received |= flags;
+    }
+
+    pub fn poll(
+        &mut self,
+        events: &mut Vec<net::Event>,
+        capacity: usize,
+        timeout: Option<Duration>,
+    ) -> Result<(), io::Error> {
+        let recv = ffi::SOCK_ASYNC_MSG_RECV as ffi::sock_async_flags_t;
+
+        // Forget the registrations of closed sockets.
+        self.registrations
+            .retain(|registration| !registration.lock().sock.is_null());
+
+        // Level-triggered, a socket stays readable until its datagrams were received.
+        for registration in &self.registrations {
+            let mut registration = registration.lock();
+            // The socket may have been closed since the registrations were cleaned up.
+            if registration.sock.is_null() {
+                continue;
+            }
+            if registration.received & recv != 0 && !has_pending(registration.sock) {
+                registration.received &= !recv;
+            }
+        }
+
+        // Sending never blocks, so a socket registered for writing is always ready.
+        let ready = self.registrations.iter().any(|registration| {
+            let registration = registration.lock();
+            registration.interest.is_writable() || registration.is_readable()
+        });
+        let timeout = if ready {
+            Some(Duration::from_secs(0))
+        } else {
+            timeout
+        };
+
+        unsafe {
+            let mut event = match timeout {
+                None => ffi::event_wait(&mut *self.queue),
+                Some(timeout) => {
+                    ffi::event_wait_timeout64(&mut *self.queue, duration_to_micros64(timeout))
+                }
+            };
+
+            // Handle every queued event, which records the flags in the registrations.
+            while !event.is_null() {
+                if let Some(handler) = (*event).handler {
+                    handler(event);
+                }
+                event = ffi::event_get(&mut *self.queue);
+            }
+        }
+
+        for registration in &self.registrations {
+            if events.len() == capacity {
+                break;
+            }
+
+            let registration = registration.lock();
+            if registration.sock.is_null() {
+                continue;
+            }
+
+            let readable = registration.is_readable();
+            let writable = registration.interest.is_writable();
+
+            let readiness = match (readable, writable) {
+                (true, true) => net::Interest::READABLE | net::Interest::WRITABLE,
+                (true, false) => net::Interest::READABLE,
+                (false, true) => net::Interest::WRITABLE,
+                (false, false) => continue,
+            };
+
+            events.push(net::Event::new(registration.token, readiness));
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Poll {
+    fn drop(&mut self) {
+        // The sockets outlive the queue their events are posted to.
+        for registration in &self.registrations {
+            registration.lock().end();
+        }
+    }
+}
+
+/// Whether the mailbox of the sock holds a received datagram.
+fn has_pending(sock: *mut ffi::sock_udp_t) -> bool {
+    unsafe { ffi::mbox_avail(&mut (*sock).reg.mbox) > 0 }
+}
+
+impl<'a> RecvBuf<'a> {
+    #[inline]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl<'a> Deref for RecvBuf<'a> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match self.data {
+            RecvData::Packet { len: 0, .. } => &[],
+            RecvData::Packet { data, len, .. } => unsafe { slice::from_raw_parts(data, len) },
+            RecvData::Copied(ref data) => data,
+        }
+    }
+}
+
+impl<'a> Drop for RecvBuf<'a> {
+    fn drop(&mut self) {
+        if let RecvData::Packet { buf_ctx, .. } = self.data {
+            release(self.sock, buf_ctx);
+        }
+    }
+}
+
+/// Calls `sock_udp_recv_buf` until it released the datagram, which nulls `buf_ctx`.
+fn release(sock: &mut ffi::sock_udp_t, mut buf_ctx: *mut ffi::c_void) {
+    let mut data = ptr::null_mut();
+    while !buf_ctx.is_null() {
+        let result =
+            unsafe { ffi::sock_udp_recv_buf(sock, &mut data, &mut buf_ctx, 0, ptr::null_mut()) };
+        if result < 0 {
+            break;
+        }
+    }
+}
+
+/// Copies the payload if GNRC split it into several snips, which precede the headers.
+unsafe fn copy_split_payload(payload: *const ffi::gnrc_pktsnip_t) -> Option<Vec<u8>> {
+    let undefined = ffi::gnrc_nettype_t_GNRC_NETTYPE_UNDEF;
+
+    let next = (*payload).next;
+    if next.is_null() || (*next).type_ != undefined {
+        return None;
+    }
+
+    let mut copied = Vec::new();
+    let mut snip = payload;
+    while !snip.is_null() && (*snip).type_ == undefined {
+        if (*snip).size > 0 {
+            let chunk = slice::from_raw_parts((*snip).data as *const u8, (*snip).size);
+            copied.extend_from_slice(chunk);
+        }
+        snip = (*snip).next;
+    }
+    Some(copied)
+}
+
+fn copy_datagram(buf: &mut [u8], data: &[u8], addr: SocketAddr) -> net::RecvInfo {
+    let len = data.len().min(buf.len());
+    buf[..len].copy_from_slice(&data[..len]);
+
+    net::RecvInfo {
+        len,
+        datagram_len: data.len(),
+        addr,
+    }
+}
+
+fn create(
+    sock: &mut ffi::sock_udp_t,
+    local: &ffi::sock_udp_ep_t,
+    remote: Option<&ffi::sock_udp_ep_t>,
+) -> Result<(), io::Error> {
+    let remote = remote.map_or(ptr::null(), |remote| remote as *const _);
+
+    let error = unsafe { ffi::sock_udp_create(sock, local, remote, 0) };
+
+    match error {
+        error if error == -(ffi::EADDRINUSE as i32) => Err(ErrorKind::AddrInUse.into()),
+        error if error == -(ffi::EAFNOSUPPORT as i32) => Err(ErrorKind::AfNoSupport.into()),
+        error if error == -(ffi::EINVAL as i32) => Err(ErrorKind::InvalidInput.into()),
+        0 => Ok(()),
+        _ => unreachable!("Unknown error occured. RIOT API changed."),
+    }
+}
+
+/// Maps the result of `sock_udp_recv`, which never blocks as the timeout is always 0.
+fn recv_result(result: isize) -> Result<usize, io::Error> {
+    match result {
+        error if error == -(ffi::EADDRNOTAVAIL as isize) => Err(ErrorKind::AddrNotAvailable.into()),
+        error if error == -(ffi::EAGAIN as isize) => Err(ErrorKind::WouldBlock.into()),
+        error if error == -(ffi::EINVAL as isize) => Err(ErrorKind::InvalidInput.into()),
+        error if error == -(ffi::ENOBUFS as isize) => Err(ErrorKind::BufferToSmall.into()),
+        error if error == -(ffi::ENOMEM as isize) => Err(ErrorKind::OutOfMemory.into()),
+        error if error == -(ffi::EPROTO as isize) => Err(ErrorKind::Protocol.into()),
+        // A timeout would be a would block, as the timeout is always 0.
+        error if error == -(ffi::ETIMEDOUT as isize) => Err(ErrorKind::WouldBlock.into()),
+        size if size >= 0 => Ok(size as _),
+        _ => unreachable!("Unknown error occured. RIOT API changed."),
+    }
+}
+
+fn send_result(result: isize) -> Result<usize, io::Error> {
+    match result {
+        error if error == -(ffi::EADDRINUSE as isize) => Err(ErrorKind::AddrInUse.into()),
+        error if error == -(ffi::EAFNOSUPPORT as isize) => Err(ErrorKind::AfNoSupport.into()),
+        error if error == -(ffi::EHOSTUNREACH as isize) => Err(ErrorKind::HostUnreachable.into()),
+        error if error == -(ffi::EINVAL as isize) => Err(ErrorKind::InvalidInput.into()),
+        error if error == -(ffi::ENOMEM as isize) => Err(ErrorKind::OutOfMemory.into()),
+        error if error == -(ffi::ENOTCONN as isize) => Err(ErrorKind::NotConnected.into()),
+        size if size >= 0 => Ok(size as _),
+        _ => unreachable!("Unknown error occurred. RIOT API changed."),
+    }
 }
 
 impl Drop for UdpSocket {
@@ -210,19 +841,263 @@ impl Drop for UdpSocket {
     }
 }
 
-pub fn eui64() -> net::Eui64 {
+/// A GNRC network interface, identified by the PID of its thread.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Interface {
+    pid: ffi::kernel_pid_t,
+}
+
+impl Interface {
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.pid as u32
+    }
+
+    /// GNRC has no interface names, the PID is used instead.
+    pub fn name(&self) -> Result<String, io::Error> {
+        self.netif()?;
+        Ok(self.pid.to_string())
+    }
+
+    pub fn mtu(&self) -> Result<u32, io::Error> {
+        let mut mtu: u16 = 0;
+        unsafe {
+            self.get(
+                ffi::netopt_t_NETOPT_MAX_PACKET_SIZE,
+                ffi::gnrc_nettype_t_GNRC_NETTYPE_IPV6 as _,
+                &mut mtu as *mut u16 as *mut _,
+                mem::size_of::<u16>(),
+            )?
+        };
+        Ok(u32::from(mtu))
+    }
+
+    /// The long address if the device has one, e.g. IEEE 802.15.4, the address otherwise.
+    pub fn link_address(&self) -> Result<Vec<u8>, io::Error> {
+        let mut address = [0; ffi::GNRC_NETIF_L2ADDR_MAXLEN as usize];
+
+        let mut get =
+            |option| unsafe { self.get(option, 0, address.as_mut_ptr() as *mut _, address.len()) };
+        let len = match get(ffi::netopt_t_NETOPT_ADDRESS_LONG) {
+            Err(ref error) if error.kind() == ErrorKind::NotSupported => {
+                get(ffi::netopt_t_NETOPT_ADDRESS)?
+            }
+            len => len?,
+        };
+
+        Ok(address[..len].to_vec())
+    }
+
+    pub fn ipv6_addrs(&self) -> Result<Vec<net::InterfaceAddr>, io::Error> {
+        let numof = unsafe { (*self.netif()?).ipv6.addrs.len() };
+
+        let mut addrs = vec![0u8; numof * mem::size_of::<ffi::ipv6_addr_t>()];
+        let len = unsafe {
+            self.get(
+                ffi::netopt_t_NETOPT_IPV6_ADDR,
+                0,
+                addrs.as_mut_ptr() as *mut _,
+                addrs.len(),
+            )?
+        };
+        addrs.truncate(len);
+
+        // The flags are in the same order as the addresses.
+        let mut flags = vec![0u8; numof];
+        unsafe {
+            self.get(
+                ffi::netopt_t_NETOPT_IPV6_ADDR_FLAGS,
+                0,
+                flags.as_mut_ptr() as *mut _,
+                numof,
+            )?
+        };
+
+        Ok(addrs
+            .chunks(mem::size_of::<ffi::ipv6_addr_t>())
+            .zip(flags)
+            .map(|(addr, raw_flags)| {
+                let addr = Ipv6Address::from_bytes(addr);
+
+                let scope = if addr.is_loopback() {
+                    net::Ipv6Scope::Host
+                } else if addr.is_link_local() {
+                    net::Ipv6Scope::Link
+                } else if addr.as_bytes()[0] == 0xfe && addr.as_bytes()[1] & 0xc0 == 0xc0 {
+                    net::Ipv6Scope::Site
+                } else {
+                    net::Ipv6Scope::Global
+                };
+
+                let raw_flags = u32::from(raw_flags);
+                let mut flags = net::Ipv6AddrFlags::empty();
+                // The state holds the remaining solicitations while tentative.
+                let state = raw_flags & ffi::GNRC_NETIF_IPV6_ADDRS_FLAGS_STATE_MASK;
+                if state & ffi::GNRC_NETIF_IPV6_ADDRS_FLAGS_STATE_TENTATIVE != 0 {
+                    flags = flags | net::Ipv6AddrFlags::TENTATIVE;
+                }
+                if state == ffi::GNRC_NETIF_IPV6_ADDRS_FLAGS_STATE_DEPRECATED {
+                    flags = flags | net::Ipv6AddrFlags::DEPRECATED;
+                }
+                if raw_flags & ffi::GNRC_NETIF_IPV6_ADDRS_FLAGS_ANYCAST != 0 {
+                    flags = flags | net::Ipv6AddrFlags::ANYCAST;
+                }
+
+                net::InterfaceAddr { addr, scope, flags }
+            })
+            .collect())
+    }
+
+    /// Devices without power states are always up.
+    pub fn is_up(&self) -> Result<bool, io::Error> {
+        let mut state: ffi::netopt_state_t = ffi::netopt_state_t_NETOPT_STATE_IDLE;
+        let result = unsafe {
+            self.get(
+                ffi::netopt_t_NETOPT_STATE,
+                0,
+                &mut state as *mut ffi::netopt_state_t as *mut _,
+                mem::size_of::<ffi::netopt_state_t>(),
+            )
+        };
+
+        match result {
+            Ok(_) => Ok(state != ffi::netopt_state_t_NETOPT_STATE_OFF
+                && state != ffi::netopt_state_t_NETOPT_STATE_SLEEP
+                && state != ffi::netopt_state_t_NETOPT_STATE_STANDBY),
+            Err(ref error) if error.kind() == ErrorKind::NotSupported => Ok(true),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn add_ipv6_addr(
+        &self,
+        addr: &Ipv6Address,
+        prefix_len: u8,
+        flags: net::Ipv6AddrFlags,
+    ) -> Result<(), io::Error> {
+        let interface = self.netif()?;
+
+        // Adding a deprecated address is rejected by the facade.
+        let mut raw_flags = if flags.contains(net::Ipv6AddrFlags::TENTATIVE) {
+            ffi::GNRC_NETIF_IPV6_ADDRS_FLAGS_STATE_TENTATIVE
+        } else {
+            ffi::GNRC_NETIF_IPV6_ADDRS_FLAGS_STATE_VALID
+        };
+        if flags.contains(net::Ipv6AddrFlags::ANYCAST) {
+            raw_flags |= ffi::GNRC_NETIF_IPV6_ADDRS_FLAGS_ANYCAST;
+        }
+
+        let mut addr_buffer = [0; 16];
+        addr_buffer.copy_from_slice(addr.as_bytes());
+
+        let mut addr = ffi::ipv6_addr_t { u8: addr_buffer };
+        let error = unsafe {
+            ffi::gnrc_netif_ipv6_addr_add(
+                interface,
+                &mut addr,
+                u32::from(prefix_len),
+                raw_flags as u8,
+            )
+        };
+
+        match error {
+            error if error == -(ffi::ENOMEM as i32) => Err(ErrorKind::OutOfMemory.into()),
+            error if error == -(ffi::ENOTSUP as i32) => Err(ErrorKind::NotSupported.into()),
+            error if error < 0 => Err(ErrorKind::InvalidInput.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn remove_ipv6_addr(&self, addr: &Ipv6Address) -> Result<(), io::Error> {
+        let interface = self.netif()?;
+
+        let mut addr_buffer = [0; 16];
+        addr_buffer.copy_from_slice(addr.as_bytes());
+
+        let mut addr = ffi::ipv6_addr_t { u8: addr_buffer };
+        let error = unsafe { ffi::gnrc_netif_ipv6_addr_remove(interface, &mut addr) };
+
+        match error {
+            error if error == -(ffi::ENOTSUP as i32) => Err(ErrorKind::NotSupported.into()),
+            error if error < 0 => Err(ErrorKind::AddrNotAvailable.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// `gnrc_netapi_get` of `option` into `data`, returning the bytes written.
+    unsafe fn get(
+        &self,
+        option: ffi::netopt_t,
+        context: u16,
+        data: *mut ffi::c_void,
+        max_len: usize,
+    ) -> Result<usize, io::Error> {
+        self.netif()?;
+
+        match ffi::gnrc_netapi_get(self.pid, option, context, data, max_len) {
+            error if error == -(ffi::ENOTSUP as i32) => Err(ErrorKind::NotSupported.into()),
+            // GNRC reports `ENOBUFS`, devices `EOVERFLOW` if `max_len` is too small.
+            error if error == -(ffi::ENOBUFS as i32) => Err(ErrorKind::BufferToSmall.into()),
+            error if error == -(ffi::EOVERFLOW as i32) => Err(ErrorKind::BufferToSmall.into()),
+            error if error < 0 => Err(ErrorKind::InvalidInput.into()),
+            len => Ok(len as usize),
+        }
+    }
+
+    /// Looks the interface up again, it may have been removed since.
+    fn netif(&self) -> Result<*mut ffi::gnrc_netif_t, io::Error> {
+        let netif = unsafe { ffi::gnrc_netif_get_by_pid(self.pid) };
+        if netif.is_null() {
+            return Err(ErrorKind::NoMatchingInterface.into());
+        }
+        Ok(netif)
+    }
+}
+
+pub fn interfaces() -> Result<Vec<Interface>, io::Error> {
+    let mut interfaces = Vec::new();
+
+    let mut next = ptr::null();
+    while let Some(netif) = unsafe { ffi::gnrc_netif_iter(next).as_ref() } {
+        next = netif;
+        interfaces.push(Interface { pid: netif.pid });
+    }
+
+    Ok(interfaces)
+}
+
+/// The interface derives the identifier from its device, whatever its link layer.
+pub fn eui64(interface: &Interface) -> Result<net::Eui64, io::Error> {
     let mut eui = ffi::eui64_t { uint8: [0; 8] };
 
-    unsafe {
-        let netif = ffi::gnrc_netif_iter(ptr::null_mut());
-        ffi::netdev_eth_get(
-            (*netif).dev,
+    let result = unsafe {
+        interface.get(
             ffi::netopt_t_NETOPT_IPV6_IID,
+            0,
             &mut eui as *mut ffi::eui64_t as *mut _,
             mem::size_of::<ffi::eui64_t>(),
-        );
+        )
+    };
+    let len = match result {
+        // The device has no identifier, like interfaces without MAC address on other OS.
+        Err(ref error) if error.kind() == ErrorKind::NotSupported => 0,
+        result => result?,
     };
+    if len != mem::size_of::<ffi::eui64_t>() {
+        return Err(ErrorKind::AddrNotAvailable.into());
+    }
 
     // union access eui is always 64-bit
-    net::Eui64(unsafe { eui.uint64.u8 })
+    Ok(net::Eui64(unsafe { eui.uint64.u8 }))
+}
+
+pub fn eui48(interface: &Interface) -> Result<net::Eui48, io::Error> {
+    let address = interface.link_address()?;
+    if address.len() != 6 {
+        return Err(ErrorKind::AddrNotAvailable.into());
+    }
+
+    let mut eui48 = [0; 6];
+    eui48.copy_from_slice(&address);
+    Ok(net::Eui48(eui48))
 }
@@ -0,0 +1,44 @@
+use riot_sys::ffi;
+
+use core::cell::UnsafeCell;
+use core::ptr;
+
+pub struct ReentrantMutex(UnsafeCell<ffi::rmutex_t>);
+
+unsafe impl Sync for ReentrantMutex {}
+unsafe impl Send for ReentrantMutex {}
+
+impl ReentrantMutex {
+    /// Equivalent to `RMUTEX_INIT`.
+    pub const unsafe fn new() -> Self {
+        ReentrantMutex(UnsafeCell::new(ffi::rmutex_t {
+            mutex: ffi::mutex_t {
+                queue: ffi::list_node {
+                    next: ptr::null_mut(),
+                },
+            },
+            refcount: 0,
+            owner: ffi::KERNEL_PID_UNDEF as _,
+        }))
+    }
+
+    #[inline]
+    pub unsafe fn lock(&self) {
+        ffi::rmutex_lock(self.0.get());
+    }
+
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        ffi::rmutex_unlock(self.0.get());
+    }
+
+    #[inline]
+    pub unsafe fn try_lock(&self) -> bool {
+        let r = ffi::rmutex_trylock(self.0.get());
+        match r {
+            1 => true,
+            0 => false,
+            _ => unreachable!(),
+        }
+    }
+}
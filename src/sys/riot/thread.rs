@@ -1,20 +1,33 @@
-use alloc::boxed::Box;
-use alloc::boxed::FnBox;
+use alloc::string::String;
 use alloc::vec::Vec;
+use core::cell::UnsafeCell;
 use core::marker::PhantomData;
 use core::mem;
 use core::ptr;
+use core::slice;
+#[cfg(feature = "develhelp")]
+use core::str;
 pub use core::time::Duration;
+use crate::io;
+use crate::sync::Mutex;
 use crate::thread;
 use crate::thread::BuilderExt;
 
+use super::thread_local;
+
 use riot_sys::ffi;
 
 pub struct JoinHandle<T = ()> {
     thread: Thread,
+    // Only taken by `join_stack` and on drop.
     #[used]
-    stack_buffer: Vec<u8>,
-    result: Option<()>,
+    stack_buffer: Option<StackBuffer>,
+    // Offset of the [Packet] in the buffer.
+    packet_offset: usize,
+    // Offset of the stack in the buffer, the canary lies right in front of it.
+    stack_offset: usize,
+    stack_size: usize,
+    stack_check: bool,
     _marker: PhantomData<T>,
 }
 
@@ -26,33 +39,402 @@ impl<T> JoinHandle<T> {
 
     #[inline]
     pub fn join(self) -> () {
-        let mut this = self;
-        this.result.take().unwrap()
+        // The buffer is released on drop.
+        self.packet().wait()
+    }
+
+    #[inline]
+    pub fn join_stack(mut self) -> Option<thread::Stack> {
+        self.packet().wait();
+
+        match self.stack_buffer.take() {
+            Some(StackBuffer::Static(stack)) => Some(stack),
+            _ => None,
+        }
+    }
+
+    /// A panic aborts on RIOT, so joining never fails.
+    #[inline]
+    pub fn join_inner(self) -> Result<(), Panic> {
+        self.join();
+        Ok(())
+    }
+
+    #[inline]
+    pub fn detach(self) {
+        // Dropping detaches.
+    }
+
+    #[inline]
+    fn buffer(&self) -> &StackBuffer {
+        // Only empty once the handle is consumed.
+        self.stack_buffer.as_ref().unwrap()
+    }
+
+    #[inline]
+    fn packet(&self) -> &Packet {
+        unsafe { &*(self.buffer().as_ptr().add(self.packet_offset) as *const Packet) }
+    }
+
+    #[inline]
+    pub fn stack_high_water_mark(&self) -> Option<usize> {
+        if !self.stack_check {
+            return None;
+        }
+
+        let stack = unsafe { self.buffer().as_ptr().add(self.stack_offset) };
+        let free = unsafe { ffi::thread_measure_stack_free(stack as *mut _) } as usize;
+        Some(self.stack_size.saturating_sub(free))
+    }
+
+    #[inline]
+    pub fn check_stack(&self) -> Result<(), thread::StackOverflow> {
+        if !self.stack_check {
+            return Ok(());
+        }
+
+        let canary = unsafe {
+            slice::from_raw_parts(
+                self.buffer().as_ptr().add(self.stack_offset - CANARY_LEN),
+                CANARY_LEN,
+            )
+        };
+
+        if canary.iter().zip(CANARY.iter().cycle()).all(|(a, b)| a == b) {
+            Ok(())
+        } else {
+            Err(thread::StackOverflow)
+        }
+    }
+}
+
+/// Pattern written below the stack of a checked thread.
+const CANARY: [u8; 4] = [0xDE, 0xAD, 0xBE, 0xEF];
+const CANARY_LEN: usize = 16;
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        reap_detached();
+
+        // Handed back by `join_stack`, the thread already exited.
+        let buffer = match self.stack_buffer.take() {
+            Some(buffer) => buffer,
+            None => return,
+        };
+
+        let detached = Detached {
+            buffer,
+            packet_offset: self.packet_offset,
+        };
+        if detached.is_finished() {
+            return;
+        }
+
+        // The thread is still running on the buffer, keep it alive until the thread exited.
+        DETACHED.lock().get_or_insert_with(Vec::new).push(detached);
     }
 }
 
+/// Stack of a thread which was still running when its [JoinHandle] was dropped.
+struct Detached {
+    buffer: StackBuffer,
+    packet_offset: usize,
+}
+
+impl Detached {
+    fn is_finished(&self) -> bool {
+        let packet = unsafe { &*(self.buffer.as_ptr().add(self.packet_offset) as *const Packet) };
+        packet.is_finished()
+    }
+}
+
+static DETACHED: Mutex<Option<Vec<Detached>>> = Mutex::new(None);
+
+/// Releases the stacks of detached threads which exited.
+fn reap_detached() {
+    if let Some(detached) = DETACHED.lock().as_mut() {
+        detached.retain(|detached| !detached.is_finished());
+    }
+}
+
+/// Memory holding a thread's name, start parameters and stack.
+enum StackBuffer {
+    Heap(Vec<u8>),
+    Static(thread::Stack),
+}
+
+impl StackBuffer {
+    #[inline]
+    fn as_ptr(&self) -> *const u8 {
+        match self {
+            StackBuffer::Heap(buffer) => buffer.as_ptr(),
+            StackBuffer::Static(buffer) => buffer.as_ptr(),
+        }
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        match self {
+            StackBuffer::Heap(buffer) => buffer.as_mut_ptr(),
+            StackBuffer::Static(buffer) => buffer.as_mut_ptr(),
+        }
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        match self {
+            StackBuffer::Heap(buffer) => buffer.capacity(),
+            StackBuffer::Static(buffer) => buffer.len(),
+        }
+    }
+}
+
+pub const PRIORITY_LEVELS: u8 = ffi::SCHED_PRIO_LEVELS as u8;
+pub const PRIORITY_MAIN: u8 = ffi::THREAD_PRIORITY_MAIN as u8;
+
+/// Thread flag used to wake up a thread blocked in [JoinHandle::join].
+const JOIN_FLAG: thread::Flags = 1 << 12;
+/// Flag used as the token of [park] and [Thread::unpark].
+const PARK_FLAG: thread::Flags = 1 << 10;
+
+/// Completion state shared between a thread and its [JoinHandle].
+struct Packet {
+    // Only accessed with interrupts disabled.
+    state: UnsafeCell<PacketState>,
+}
+
+struct PacketState {
+    finished: bool,
+    joiner: Option<ThreadId>,
+}
+
+unsafe impl Sync for Packet {}
+unsafe impl Send for Packet {}
+
+impl Packet {
+    fn new() -> Self {
+        Packet {
+            state: UnsafeCell::new(PacketState {
+                finished: false,
+                joiner: None,
+            }),
+        }
+    }
+
+    /// Marks the thread as finished and wakes up a joining thread.
+    ///
+    /// Interrupts stay disabled, so the calling thread must exit right away. Therefore a thread
+    /// observed as finished by another thread has exited and no longer uses its stack.
+    unsafe fn finish(&self) {
+        ffi::irq_disable();
+
+        let state = &mut *self.state.get();
+        state.finished = true;
+        if let Some(joiner) = state.joiner {
+            Thread { id: joiner }.set_flags(JOIN_FLAG);
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        unsafe {
+            let irq = ffi::irq_disable();
+            let finished = (*self.state.get()).finished;
+            ffi::irq_restore(irq);
+            finished
+        }
+    }
+
+    /// Blocks until the thread called [Packet::finish].
+    fn wait(&self) {
+        loop {
+            unsafe {
+                let irq = ffi::irq_disable();
+                let state = &mut *self.state.get();
+                if state.finished {
+                    ffi::irq_restore(irq);
+                    return;
+                }
+                state.joiner = Some(current().id);
+                ffi::irq_restore(irq);
+            }
+
+            wait_flags_any(JOIN_FLAG, None);
+        }
+    }
+}
+
+/// Everything passed to the thread context, placed in front of the stack.
+#[repr(C)]
+struct Start<F> {
+    // First field, so that a pointer to the start is a pointer to the packet.
+    packet: Packet,
+    main: F,
+}
+
+#[inline]
+fn align_up(address: usize, align: usize) -> usize {
+    (address + align - 1) & !(align - 1)
+}
+
 /// A handle to a thread
 pub struct Thread {
     id: ThreadId,
 }
 
 impl Thread {
+    /// Safe to call from interrupt context.
     #[inline]
     pub fn unpark(&self) {
-        unsafe {
-            let _ = ffi::thread_wakeup(self.id.0);
-        }
+        self.set_flags(PARK_FLAG)
     }
 
     #[inline]
     pub fn id(&self) -> ThreadId {
         self.id
     }
+
+    /// RIOT only keeps names if built with `DEVELHELP`, which the `develhelp` feature declares.
+    #[cfg(feature = "develhelp")]
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        unsafe {
+            let name = ffi::thread_getname(self.id.0);
+            if name.is_null() {
+                return None;
+            }
+
+            let name = name as *const u8;
+            let mut len = 0;
+            while *name.add(len) != 0 {
+                len += 1;
+            }
+
+            str::from_utf8(slice::from_raw_parts(name, len)).ok()
+        }
+    }
+
+    /// RIOT only keeps names if built with `DEVELHELP`, which the `develhelp` feature declares.
+    #[cfg(not(feature = "develhelp"))]
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Safe to call from interrupt context. Does nothing if the thread exited.
+    #[inline]
+    pub fn set_flags(&self, mask: thread::Flags) {
+        unsafe {
+            // The thread cannot exit between the lookup and setting the flags.
+            let irq = ffi::irq_disable();
+            let thread = ffi::sched_threads[self.id.0 as usize];
+            if !thread.is_null() {
+                ffi::thread_flags_set(thread as *mut _, mask);
+            }
+            ffi::irq_restore(irq);
+        }
+    }
+}
+
+#[inline]
+pub fn wait_flags_any(mask: thread::Flags, timeout: Option<Duration>) -> Option<thread::Flags> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return Some(unsafe { ffi::thread_flags_wait_any(mask) }),
+    };
+
+    unsafe {
+        let mut timer = mem::zeroed();
+        ffi::xtimer_set_timeout_flag(&mut timer, duration_to_micros(timeout));
+
+        let received = ffi::thread_flags_wait_any(mask | ffi::THREAD_FLAG_TIMEOUT as thread::Flags);
+
+        ffi::xtimer_remove(&mut timer);
+        // The timer may have fired in the meantime.
+        ffi::thread_flags_clear(ffi::THREAD_FLAG_TIMEOUT as _);
+
+        match received & mask {
+            0 => None,
+            received => Some(received),
+        }
+    }
+}
+
+#[inline]
+pub fn wait_flags_all(mask: thread::Flags, timeout: Option<Duration>) -> Option<thread::Flags> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return Some(unsafe { ffi::thread_flags_wait_all(mask) }),
+    };
+
+    unsafe {
+        let mut timer = mem::zeroed();
+        ffi::xtimer_set_timeout_flag(&mut timer, duration_to_micros(timeout));
+
+        // thread_flags_wait_all would wait for the timeout flag as well,
+        // so collect the flags one by one.
+        let mut received = 0;
+        let timed_out = loop {
+            let flags = ffi::thread_flags_wait_any(
+                (mask & !received) | ffi::THREAD_FLAG_TIMEOUT as thread::Flags,
+            );
+            received |= flags & mask;
+
+            if received == mask {
+                break false;
+            }
+            if flags & ffi::THREAD_FLAG_TIMEOUT as thread::Flags != 0 {
+                break true;
+            }
+        };
+
+        ffi::xtimer_remove(&mut timer);
+        ffi::thread_flags_clear(ffi::THREAD_FLAG_TIMEOUT as _);
+
+        if timed_out {
+            // Hand back the flags which were consumed while waiting.
+            current().set_flags(received);
+            None
+        } else {
+            Some(received)
+        }
+    }
+}
+
+#[inline]
+pub fn clear_flags(mask: thread::Flags) -> thread::Flags {
+    unsafe { ffi::thread_flags_clear(mask) }
+}
+
+/// Saturates at `u32::MAX` microseconds, which is a bit more than an hour.
+fn duration_to_micros(duration: Duration) -> u32 {
+    let micros = duration_to_micros64(duration);
+    if micros > u64::from(u32::max_value()) {
+        u32::max_value()
+    } else {
+        micros as u32
+    }
+}
+
+pub(crate) fn duration_to_micros64(duration: Duration) -> u64 {
+    duration
+        .as_secs()
+        .saturating_mul(1_000_000)
+        .saturating_add(u64::from(duration.subsec_micros()))
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+/// A unique identifier for a running thread.
+///
+/// RIOT reuses the identifier of an exited thread.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub struct ThreadId(ffi::kernel_pid_t);
 
+impl ThreadId {
+    #[inline]
+    pub fn as_u64(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
 #[inline]
 pub fn current() -> Thread {
     // RIOT does it the same way with an inlined function.
@@ -61,14 +443,15 @@ pub fn current() -> Thread {
 }
 
 #[inline]
-pub fn sleep(_duration: Duration) {
-    // TODO
-    unimplemented!()
+pub fn sleep(duration: Duration) {
+    unsafe { ffi::xtimer_usleep64(duration_to_micros64(duration)) }
 }
 
 #[inline]
 pub fn park() {
-    unsafe { ffi::thread_sleep() }
+    unsafe {
+        ffi::thread_flags_wait_any(PARK_FLAG);
+    }
 }
 
 #[inline]
@@ -76,54 +459,179 @@ pub fn panicking() -> bool {
     false
 }
 
+/// A thread's panic, which never reaches a joining thread on RIOT.
+pub enum Panic {}
+
+pub fn resume_unwind(panic: Panic) -> ! {
+    match panic {}
+}
+
+/// A panic aborts on RIOT, so this only runs `f`.
 #[inline]
-pub fn park_timeout(_duration: Duration) {
-    unimplemented!("RIOT does not support timeouts")
+pub fn catch_unwind<F: FnOnce()>(f: F) -> Result<(), Panic> {
+    f();
+    Ok(())
 }
 
 #[inline]
-unsafe fn spawn_inner<'a>(
-    p: Box<dyn FnBox() -> () + Send + 'a>,
+pub fn park_timeout(duration: Duration) {
+    wait_flags_any(PARK_FLAG, Some(duration));
+}
+
+#[inline]
+unsafe fn spawn_inner<F>(
+    main: F,
     name: &'static str,
+    stack: Option<thread::Stack>,
     stack_size: i32,
     flags: i32,
     priority: u32,
-) -> Result<JoinHandle<()>, thread::SpawnError> {
-    let p = box p;
+    stack_check: bool,
+) -> Result<JoinHandle<()>, thread::SpawnError>
+where
+    F: FnOnce() -> (),
+    F: Send,
+{
+    // The buffer is laid out as name, start parameters, canary and then the stack. RIOT keeps a
+    // pointer to the name, so a name without NUL terminator is copied into the buffer, where it
+    // lives as long as the thread.
+    let name_len = if name.ends_with('\0') {
+        0
+    } else {
+        name.len() + 1
+    };
+    let canary_len = if stack_check { CANARY_LEN } else { 0 };
+    // Upper bound including the alignment padding of the start parameters and the stack.
+    let header_len = name_len
+        + mem::align_of::<Start<F>>()
+        + mem::size_of::<Start<F>>()
+        + canary_len
+        + mem::align_of::<usize>();
 
-    let mut buffer = Vec::with_capacity(stack_size as usize);
+    let mut buffer = match stack {
+        Some(stack) => StackBuffer::Static(stack),
+        None if stack_size < 0 => return Err(thread::SpawnError::StackTooSmall),
+        None => {
+            let mut buffer = Vec::new();
+            buffer
+                .try_reserve_exact(header_len + stack_size as usize)
+                .map_err(|_| thread::SpawnError::OutOfMemory)?;
+            StackBuffer::Heap(buffer)
+        }
+    };
+
+    if buffer.capacity() <= header_len {
+        return Err(thread::SpawnError::StackTooSmall);
+    }
+
+    let base = buffer.as_mut_ptr();
+    let start_offset =
+        align_up(base as usize + name_len, mem::align_of::<Start<F>>()) - base as usize;
+    // RIOT and `thread_measure_stack_free` expect a word-aligned stack.
+    let stack_offset = align_up(
+        base as usize + start_offset + mem::size_of::<Start<F>>() + canary_len,
+        mem::align_of::<usize>(),
+    ) - base as usize;
+    let canary_offset = stack_offset - canary_len;
+    // A provided buffer is used completely.
+    let stack_size = buffer.capacity() - stack_offset;
+
+    if stack_size < ffi::THREAD_STACKSIZE_MINIMUM as usize {
+        return Err(thread::SpawnError::StackTooSmall);
+    }
+
+    let name = if name_len == 0 {
+        name.as_ptr()
+    } else {
+        ptr::copy_nonoverlapping(name.as_ptr(), base, name.len());
+        *base.add(name.len()) = 0;
+        base as *const u8
+    };
+
+    let start = base.add(start_offset) as *mut Start<F>;
+    ptr::write(
+        start,
+        Start {
+            packet: Packet::new(),
+            main,
+        },
+    );
+
+    // An overflowing thread overwrites the canary first.
+    for (i, byte) in CANARY.iter().cycle().take(canary_len).enumerate() {
+        *base.add(canary_offset + i) = *byte;
+    }
+
+    let flags = if stack_check {
+        flags | ffi::THREAD_CREATE_STACKTEST as i32
+    } else {
+        flags
+    };
 
     let id = ffi::thread_create(
-        buffer.as_mut_ptr(),
-        stack_size,
+        base.add(stack_offset),
+        stack_size as i32,
         priority as _,
         flags,
-        Some(thread_start),
-        &*p as *const _ as *mut _, // Parameters
-        name.as_ptr(),
+        Some(thread_start::<F>),
+        start as *mut _, // Parameters
+        name,
     );
 
-    assert!(id > 0, "thread id is invalid");
+    extern "C" fn thread_start<F>(start: *mut ffi::c_void) -> *mut ffi::c_void
+    where
+        F: FnOnce() -> (),
+    {
+        unsafe {
+            let start = start as *mut Start<F>;
+            // Move the closure onto the thread's own stack.
+            let main = ptr::read(&(*start).main);
+            main();
+            thread_local::run_dtors();
+            // Stacks of other detached threads are released as soon as possible, this thread's
+            // own is left to the next spawn, drop or exit.
+            reap_detached();
 
-    extern "C" fn thread_start(main: *mut ffi::c_void) -> *mut ffi::c_void {
-        unsafe { Box::from_raw(main as *mut Box<dyn FnBox()>)() }
+            // With interrupts disabled the joining thread cannot run and release the stack
+            // before this thread exited. The scheduler enables them again after switching away.
+            (*start).packet.finish();
+        }
         ptr::null_mut()
     }
 
     match id {
-        error if error == -(ffi::EINVAL as i16) => Err(thread::SpawnError::SpawnFailed),
-        error if error == -(ffi::EOVERFLOW as i16) => Err(thread::SpawnError::SpawnFailed),
-        pid => {
-            mem::forget(p); // Everything passed to the thread context
-
-            Ok(JoinHandle {
-                _marker: PhantomData,
-                thread: Thread { id: ThreadId(pid) },
-                stack_buffer: buffer,
-                result: None,
+        // The thread never started, so the closure is still in the buffer.
+        error if error < 0 => {
+            ptr::drop_in_place(&mut (*start).main);
+
+            Err(match error {
+                error if error == -(ffi::EINVAL as i16) => thread::SpawnError::InvalidPriority,
+                error if error == -(ffi::EOVERFLOW as i16) => thread::SpawnError::TooManyThreads,
+                error => thread::SpawnError::Os(i32::from(error)),
             })
         }
+        pid => Ok(JoinHandle {
+            _marker: PhantomData,
+            thread: Thread { id: ThreadId(pid) },
+            stack_buffer: Some(buffer),
+            packet_offset: start_offset,
+            stack_offset,
+            stack_size,
+            stack_check,
+        }),
+    }
+}
+
+fn raw_flags(flags: thread::CreateFlags) -> i32 {
+    let mut raw = 0;
+    if flags.contains(thread::CreateFlags::SLEEPING) {
+        raw |= ffi::THREAD_CREATE_SLEEPING;
     }
+    if flags.contains(thread::CreateFlags::WOUT_YIELD) {
+        raw |= ffi::THREAD_CREATE_WOUT_YIELD;
+    }
+    // The stack test flag is set together with the canary.
+    raw as i32
 }
 
 #[inline]
@@ -136,6 +644,123 @@ where
     B::new().spawn(f).expect("thread spawn failed")
 }
 
+/// Distance between the current stack pointer, approximated by a local, and the stack's end.
+#[inline]
+pub fn stack_remaining() -> Option<usize> {
+    let marker = 0u8;
+    unsafe {
+        let thread = ptr::read_volatile(&ffi::sched_active_thread as *const _);
+        if thread.is_null() {
+            return None;
+        }
+        let stack_start = (*thread).stack_start as usize;
+        Some((&marker as *const u8 as usize).saturating_sub(stack_start))
+    }
+}
+
+pub fn list() -> Result<Vec<thread::ThreadInfo>, io::Error> {
+    // Allocated up front, nothing may allocate while interrupts are disabled.
+    let mut raw = Vec::with_capacity(ffi::MAXTHREADS as usize);
+
+    // Threads must not exit and release their stacks during the walk.
+    let irq = unsafe { ffi::irq_disable() };
+
+    for &thread in unsafe { ffi::sched_threads.iter() } {
+        if thread.is_null() {
+            continue;
+        }
+        raw.push(unsafe { RawInfo::copy(&*thread) });
+    }
+
+    unsafe { ffi::irq_restore(irq) };
+
+    Ok(raw.iter().map(RawInfo::to_info).collect())
+}
+
+/// Longest thread name [list] reports, longer names are cut off.
+const NAME_LEN: usize = 32;
+
+/// What [list] copies of a thread while interrupts are disabled.
+struct RawInfo {
+    pid: ffi::kernel_pid_t,
+    status: u32,
+    priority: u8,
+    stack_size: Option<usize>,
+    stack_used: Option<usize>,
+    name: [u8; NAME_LEN],
+    name_len: Option<usize>,
+}
+
+impl RawInfo {
+    unsafe fn copy(thread: &ffi::thread_t) -> Self {
+        let mut info = RawInfo {
+            pid: thread.pid,
+            status: u32::from(thread.status),
+            priority: thread.priority,
+            stack_size: None,
+            stack_used: None,
+            name: [0; NAME_LEN],
+            name_len: None,
+        };
+        info.copy_develhelp(thread);
+        info
+    }
+
+    /// RIOT only keeps the stack size and the name if built with `DEVELHELP`.
+    #[cfg(feature = "develhelp")]
+    unsafe fn copy_develhelp(&mut self, thread: &ffi::thread_t) {
+        let stack_size = thread.stack_size as usize;
+        self.stack_size = Some(stack_size);
+        if has_stack_test(thread.stack_start) {
+            let free = ffi::thread_measure_stack_free(thread.stack_start) as usize;
+            self.stack_used = Some(stack_size.saturating_sub(free));
+        }
+
+        let name = thread.name as *const u8;
+        if !name.is_null() {
+            let mut len = 0;
+            while len < NAME_LEN && *name.add(len) != 0 {
+                self.name[len] = *name.add(len);
+                len += 1;
+            }
+            self.name_len = Some(len);
+        }
+    }
+
+    #[cfg(not(feature = "develhelp"))]
+    unsafe fn copy_develhelp(&mut self, _thread: &ffi::thread_t) {}
+
+    fn to_info(&self) -> thread::ThreadInfo {
+        let status = match self.status {
+            ffi::STATUS_RUNNING => thread::ThreadStatus::Running,
+            ffi::STATUS_PENDING => thread::ThreadStatus::Ready,
+            ffi::STATUS_SLEEPING => thread::ThreadStatus::Sleeping,
+            ffi::STATUS_STOPPED => thread::ThreadStatus::Stopped,
+            // Every other state waits on a mutex, a message, flags or a mailbox.
+            _ => thread::ThreadStatus::Blocked,
+        };
+
+        thread::ThreadInfo {
+            id: ThreadId(self.pid),
+            name: self
+                .name_len
+                .map(|len| String::from_utf8_lossy(&self.name[..len]).into_owned()),
+            priority: i32::from(self.priority),
+            status,
+            stack_size: self.stack_size,
+            stack_used: self.stack_used,
+        }
+    }
+}
+
+/// Whether the stack was filled with the pattern of `THREAD_CREATE_STACKTEST`, where each word
+/// holds its own address. Without the flag RIOT only writes the lowest word as a guard.
+#[cfg(feature = "develhelp")]
+unsafe fn has_stack_test(stack_start: *mut ffi::c_char) -> bool {
+    let words = stack_start as *const usize;
+    *words == words as usize && *words.add(1) == words.add(1) as usize
+}
+
 #[inline]
 pub fn yield_now() {
     unsafe { ffi::thread_yield() }
@@ -145,8 +770,10 @@ pub fn yield_now() {
 pub struct Builder {
     name: Option<&'static str>,
     stack_size: Option<i32>,
-    priority: Option<u32>,
-    flags: Option<i32>,
+    priority: Option<thread::Priority>,
+    flags: Option<thread::CreateFlags>,
+    stack_check: Option<bool>,
+    stack: Option<thread::Stack>,
 }
 
 impl BuilderExt for Builder {
@@ -171,17 +798,29 @@ impl BuilderExt for Builder {
     }
 
     #[inline]
-    fn priority(mut self, priority: u32) -> Self {
+    fn priority(mut self, priority: thread::Priority) -> Self {
         self.priority = Some(priority);
         self
     }
 
     #[inline]
-    fn flags(mut self, flags: i32) -> Self {
+    fn flags(mut self, flags: thread::CreateFlags) -> Self {
         self.flags = Some(flags);
         self
     }
 
+    #[inline]
+    fn stack_check(mut self, enabled: bool) -> Self {
+        self.stack_check = Some(enabled);
+        self
+    }
+
+    #[inline]
+    fn stack(mut self, stack: thread::Stack) -> Self {
+        self.stack = Some(stack);
+        self
+    }
+
     #[inline]
     fn spawn<F>(self, f: F) -> Result<Self::JoinHandle, thread::SpawnError>
     where
@@ -193,14 +832,31 @@ impl BuilderExt for Builder {
             stack_size,
             flags,
             priority,
+            stack_check,
+            stack,
         } = self;
 
-        let name = name.unwrap_or("rust_thread");
+        let name = name.unwrap_or("rust_thread\0");
         let stack_size = stack_size.unwrap_or(512);
-        let flags = flags.unwrap_or(0);
-        // TODO probably should warn about the default behaviour
-        let priority = priority.unwrap_or(ffi::THREAD_PRIORITY_MAIN - 1);
+        let flags = flags.unwrap_or_default();
+        // Slightly more urgent than main, as RIOT's own threads are.
+        let priority = priority.unwrap_or(thread::Priority::MAIN.higher(1));
+        let stack_check =
+            stack_check.unwrap_or(false) || flags.contains(thread::CreateFlags::STACKTEST);
 
-        unsafe { spawn_inner(box f, name, stack_size, flags, priority).map(From::from) }
+        reap_detached();
+
+        unsafe {
+            spawn_inner(
+                f,
+                name,
+                stack,
+                stack_size,
+                raw_flags(flags),
+                u32::from(priority.as_raw()),
+                stack_check,
+            )
+            .map(From::from)
+        }
     }
 }
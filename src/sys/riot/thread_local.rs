@@ -0,0 +1,162 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr;
+
+use riot_sys::ffi;
+
+struct Entry {
+    key: usize,
+    value: *mut u8,
+    dtor: unsafe fn(*mut u8),
+}
+
+struct Table {
+    owner: Owner,
+    entries: Vec<Entry>,
+}
+
+/// Identifies the thread which created a table. A later thread reusing the pid has another
+/// control block or stack.
+#[derive(PartialEq, Eq)]
+struct Owner {
+    thread: *const ffi::thread_t,
+    stack_start: *const ffi::c_char,
+}
+
+const TABLE_COUNT: usize = ffi::MAXTHREADS as usize;
+
+/// `PTHREAD_DESTRUCTOR_ITERATIONS`
+const DTOR_PASSES: usize = 4;
+
+// One table per pid. A table is only ever touched by the thread owning the pid, so no locking is
+// required. Only raw pointers are handed out, as accessing a key may re-enter through an
+// initializer or destructor.
+static mut TABLES: [*mut Table; TABLE_COUNT] = [ptr::null_mut(); TABLE_COUNT];
+
+/// A thread local storage key which owns its contents.
+///
+/// Values are stored per [ThreadId] and lazily initialized on the first call to [with]. The
+/// destructors are run when a thread spawned through os-glue exits. Threads created by other
+/// means, e.g. the main thread, never run them. Their values are dropped by the next thread
+/// using the same pid instead. A thread which is not spawned through os-glue and reuses the pid,
+/// the control block and the stack cannot be told apart and inherits the stale values.
+///
+/// Destructors which initialize keys again are run up to four times, as with pthreads. Values
+/// initialized after that are leaked.
+///
+/// [ThreadId]: crate::thread::ThreadId
+/// [with]: LocalKey::with
+pub struct LocalKey<T: 'static> {
+    init: fn() -> T,
+}
+
+impl<T: 'static> LocalKey<T> {
+    #[doc(hidden)]
+    pub const fn new(init: fn() -> T) -> LocalKey<T> {
+        LocalKey { init }
+    }
+
+    /// Acquires a reference to the value in this TLS key.
+    ///
+    /// This will lazily initialize the value if this thread has not referenced this key yet.
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let key = self as *const _ as usize;
+
+        let existing = unsafe {
+            (*current_table())
+                .entries
+                .iter()
+                .find(|entry| entry.key == key)
+                .map(|entry| entry.value as *const T)
+        };
+
+        let value = match existing {
+            Some(value) => value,
+            None => {
+                // The initializer may access other keys, so the table is fetched again afterwards.
+                let value = Box::into_raw(Box::new((self.init)()));
+                unsafe {
+                    (*current_table()).entries.push(Entry {
+                        key,
+                        value: value as *mut u8,
+                        dtor: drop_value::<T>,
+                    })
+                };
+                value
+            }
+        };
+
+        // The values are boxed, growing the table does not move them.
+        f(unsafe { &*value })
+    }
+}
+
+unsafe fn drop_value<T>(value: *mut u8) {
+    drop(Box::from_raw(value as *mut T))
+}
+
+unsafe fn current_slot() -> *mut *mut Table {
+    // RIOT does it the same way with an inlined function.
+    let pid = ptr::read_volatile(&ffi::sched_active_pid as *const _);
+    &mut TABLES[pid as usize]
+}
+
+unsafe fn current_owner() -> Owner {
+    let thread = ptr::read_volatile(&ffi::sched_active_thread as *const _);
+    Owner {
+        thread,
+        stack_start: (*thread).stack_start,
+    }
+}
+
+unsafe fn current_table() -> *mut Table {
+    let slot = current_slot();
+    let owner = current_owner();
+
+    if !(*slot).is_null() && (**slot).owner != owner {
+        // Left behind by an exited thread which was not spawned through os-glue. Destructors
+        // accessing a key create the table of the current thread.
+        let stale = Box::from_raw(*slot);
+        *slot = ptr::null_mut();
+
+        for entry in stale.entries.into_iter() {
+            (entry.dtor)(entry.value);
+        }
+    }
+
+    if (*slot).is_null() {
+        *slot = Box::into_raw(Box::new(Table {
+            owner,
+            entries: Vec::new(),
+        }));
+    }
+    *slot
+}
+
+/// Runs the destructors of the current thread's locals. Called before a spawned thread exits.
+pub(crate) unsafe fn run_dtors() {
+    let slot = current_slot();
+
+    // Destructors may initialize keys again, which creates a new table.
+    for _ in 0..DTOR_PASSES {
+        if (*slot).is_null() {
+            return;
+        }
+
+        let table = Box::from_raw(*slot);
+        *slot = ptr::null_mut();
+
+        for entry in table.entries.into_iter() {
+            (entry.dtor)(entry.value);
+        }
+    }
+
+    // Leaks the values initialized by the last pass, instead of spinning forever.
+    if !(*slot).is_null() {
+        drop(Box::from_raw(*slot));
+        *slot = ptr::null_mut();
+    }
+}
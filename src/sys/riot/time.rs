@@ -1,7 +1,14 @@
 use crate::time::Duration;
 use riot_sys::ffi;
 
+use core::future::Future;
+use core::marker::Pinned;
+use core::mem;
 use core::ops::{Add, Sub};
+use core::pin::Pin;
+use core::task::{LocalWaker, Poll, Waker};
+
+use super::thread::duration_to_micros64;
 
 #[derive(Debug, Copy, Clone)]
 pub struct Instant {
@@ -99,3 +106,88 @@ impl Sub<Duration> for Instant {
         self.sub_duration(other)
     }
 }
+
+/// Future completing once an xtimer fired, which is armed on the first poll.
+pub struct Sleep {
+    timer: ffi::xtimer_t,
+    // In microseconds of `xtimer_now_usec64`.
+    deadline: u64,
+    armed: bool,
+    // Written by the timer callback, accessed with interrupts disabled.
+    fired: bool,
+    // Only used by the timer callback, which must not drop it.
+    waker: Option<Waker>,
+    // The timer points to the future.
+    _pinned: Pinned,
+}
+
+impl Sleep {
+    pub fn new(duration: Duration) -> Sleep {
+        Sleep {
+            timer: unsafe { mem::zeroed() },
+            deadline: unsafe { ffi::xtimer_now_usec64() }
+                .saturating_add(duration_to_micros64(duration)),
+            armed: false,
+            fired: false,
+            waker: None,
+            _pinned: Pinned,
+        }
+    }
+
+    /// Runs in interrupt context, the waker is dropped later by the polling thread.
+    unsafe extern "C" fn fire(arg: *mut ffi::c_void) {
+        let sleep = &mut *(arg as *mut Sleep);
+        sleep.fired = true;
+        if let Some(ref waker) = sleep.waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+        unsafe {
+            let this = Pin::get_mut_unchecked(self);
+
+            let mut waker = Some(lw.clone().into_waker());
+
+            let irq = ffi::irq_disable();
+            let fired = this.fired;
+            if !fired {
+                mem::swap(&mut this.waker, &mut waker);
+            }
+            ffi::irq_restore(irq);
+
+            // Neither the new nor the replaced waker is dropped with interrupts disabled.
+            drop(waker);
+
+            if fired {
+                return Poll::Ready(());
+            }
+
+            if !this.armed {
+                let now = ffi::xtimer_now_usec64();
+                if now >= this.deadline {
+                    return Poll::Ready(());
+                }
+
+                this.armed = true;
+                this.timer.callback = Some(Sleep::fire);
+                this.timer.arg = this as *mut Sleep as *mut _;
+                ffi::xtimer_set64(&mut this.timer, this.deadline - now);
+            }
+
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if self.armed {
+            unsafe { ffi::xtimer_remove(&mut self.timer) }
+        }
+    }
+}
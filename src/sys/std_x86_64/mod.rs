@@ -0,0 +1,135 @@
+use std::cell::UnsafeCell;
+use std::fmt;
+pub use std::io::{Error, ErrorKind};
+pub use std::net::{
+    IpAddr as IpAddress, Ipv4Addr as Ipv4Address, Ipv6Addr as Ipv6Address, SocketAddr,
+};
+use std::ops::{Add, Sub};
+use std::sync::atomic::{AtomicUsize, Ordering};
+pub use std::time::Duration;
+
+mod net;
+mod reactor;
+mod thread;
+
+pub use self::net::*;
+pub use self::reactor::Sleep;
+pub use self::thread::*;
+
+#[allow(dead_code)]
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments) {
+    use std::io;
+    use std::io::Write;
+
+    let stdout = io::stdout();
+    let mut guard = stdout.lock();
+
+    guard.write_fmt(args).unwrap()
+}
+
+/// Spinning reentrant lock, `std` does not expose its own.
+pub struct ReentrantMutex {
+    owner: AtomicUsize,
+    count: UnsafeCell<usize>,
+}
+
+unsafe impl Sync for ReentrantMutex {}
+unsafe impl Send for ReentrantMutex {}
+
+impl ReentrantMutex {
+    pub const unsafe fn new() -> Self {
+        ReentrantMutex {
+            owner: AtomicUsize::new(0),
+            count: UnsafeCell::new(0),
+        }
+    }
+
+    #[inline]
+    pub unsafe fn lock(&self) {
+        while !self.try_lock() {
+            yield_now();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn try_lock(&self) -> bool {
+        let this = owner_token();
+
+        if self.owner.load(Ordering::Relaxed) != this
+            && self.owner.compare_and_swap(0, this, Ordering::Acquire) != 0
+        {
+            return false;
+        }
+
+        *self.count.get() += 1;
+        true
+    }
+
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        let count = self.count.get();
+        *count -= 1;
+        if *count == 0 {
+            self.owner.store(0, Ordering::Release);
+        }
+    }
+}
+
+/// Non-zero value unique to the current thread, the address of a thread local.
+fn owner_token() -> usize {
+    thread_local!(static TOKEN: u8 = 0);
+    TOKEN.with(|token| token as *const u8 as usize)
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug)]
+pub struct Instant {
+    timespec: time::Timespec,
+}
+
+impl Instant {
+    #[inline]
+    pub fn now() -> Self {
+        let tm = time::now();
+        let timespec = tm.to_timespec();
+        Instant { timespec }
+    }
+
+    #[inline]
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        let duration = self.timespec - earlier.timespec;
+        duration.to_std().unwrap()
+    }
+
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        let now = Instant::now();
+        now.duration_since(*self)
+    }
+}
+
+impl From<(i32, u32)> for Instant {
+    fn from((sec, nsec): (i32, u32)) -> Self {
+        Instant {
+            timespec: time::Timespec::new(i64::from(sec), nsec as _),
+        }
+    }
+}
+
+impl Sub<crate::time::Duration> for Instant {
+    type Output = Instant;
+    fn sub(self, other: Duration) -> Self::Output {
+        Instant {
+            timespec: self.timespec - time::Duration::from_std(other).unwrap(),
+        }
+    }
+}
+
+impl Add<crate::time::Duration> for Instant {
+    type Output = Instant;
+    fn add(self, other: Duration) -> Self::Output {
+        Instant {
+            timespec: self.timespec + time::Duration::from_std(other).unwrap(),
+        }
+    }
+}
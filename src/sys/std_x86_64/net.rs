@@ -0,0 +1,1081 @@
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::mem;
+use std::net::{self as std_net, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::ops::Deref;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::ptr;
+use std::task::{self, LocalWaker};
+use std::time::Duration;
+
+use super::reactor::{timeout_millis, Reactor};
+use crate::net;
+
+/// The UDP socket of the standard library, extended by what it lacks.
+pub struct UdpSocket {
+    inner: std_net::UdpSocket,
+    // `std` has no stable way to query the peer.
+    peer: Option<SocketAddr>,
+    // Receives the datagrams of `recv_buf`, allocated on first use.
+    recv_buffer: Vec<u8>,
+}
+
+/// A datagram in the buffer of a [UdpSocket].
+pub struct RecvBuf<'a> {
+    data: &'a [u8],
+    addr: SocketAddr,
+}
+
+/// The largest payload of a UDP datagram.
+const MAX_DATAGRAM: usize = 65_507;
+
+impl UdpSocket {
+    #[inline]
+    pub fn bind(local: SocketAddr) -> Result<Self, io::Error> {
+        Ok(UdpSocket {
+            inner: std_net::UdpSocket::bind(local)?,
+            peer: None,
+            recv_buffer: Vec::new(),
+        })
+    }
+
+    pub fn bind_on(local: SocketAddr, interface: &Interface) -> Result<Self, io::Error> {
+        let family = match local {
+            SocketAddr::V4(..) => libc::AF_INET,
+            SocketAddr::V6(..) => libc::AF_INET6,
+        };
+
+        let fd = unsafe { libc::socket(family, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // Closes the socket on error.
+        let inner = unsafe { std_net::UdpSocket::from_raw_fd(fd) };
+
+        let name = interface.name()?;
+        let error = unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_BINDTODEVICE,
+                name.as_ptr() as *const _,
+                name.len() as libc::socklen_t,
+            )
+        };
+        if error != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let (storage, storage_len) = raw_socket_addr(&local);
+        let error = unsafe { libc::bind(fd, &storage as *const _ as *const _, storage_len) };
+        if error != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(UdpSocket {
+            inner,
+            peer: None,
+            recv_buffer: Vec::new(),
+        })
+    }
+
+    #[inline]
+    pub fn connect(&mut self, remote: SocketAddr) -> Result<(), io::Error> {
+        self.inner.connect(remote)?;
+        self.peer = Some(remote);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn recv_from(&mut self, buf: &mut [u8]) -> Result<(usize, SocketAddr), io::Error> {
+        self.inner.recv_from(buf)
+    }
+
+    pub fn recv_buf(&mut self) -> Result<RecvBuf, io::Error> {
+        self.recv_buffer.resize(MAX_DATAGRAM, 0);
+
+        let (len, addr) = self.inner.recv_from(&mut self.recv_buffer)?;
+        Ok(RecvBuf {
+            data: &self.recv_buffer[..len],
+            addr,
+        })
+    }
+
+    pub fn recv_from_with_flags(
+        &mut self,
+        buf: &mut [u8],
+        flags: net::RecvFlags,
+    ) -> Result<net::RecvInfo, io::Error> {
+        // Makes recvfrom return the length of the whole datagram.
+        let mut raw_flags = libc::MSG_TRUNC;
+        if flags.contains(net::RecvFlags::PEEK) {
+            raw_flags |= libc::MSG_PEEK;
+        }
+
+        let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+        let mut storage_len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+
+        let datagram_len = unsafe {
+            libc::recvfrom(
+                self.inner.as_raw_fd(),
+                buf.as_mut_ptr() as *mut _,
+                buf.len(),
+                raw_flags,
+                &mut storage as *mut _ as *mut _,
+                &mut storage_len,
+            )
+        };
+
+        if datagram_len < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let datagram_len = datagram_len as usize;
+
+        Ok(net::RecvInfo {
+            len: datagram_len.min(buf.len()),
+            datagram_len,
+            addr: socket_addr(&storage)?,
+        })
+    }
+
+    #[inline]
+    pub fn recv(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        self.inner.recv(buf)
+    }
+
+    #[inline]
+    pub fn send_to(&mut self, buf: &[u8], addr: SocketAddr) -> Result<usize, io::Error> {
+        self.inner.send_to(buf, addr)
+    }
+
+    #[inline]
+    pub fn send(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        self.inner.send(buf)
+    }
+
+    #[inline]
+    pub fn local_addr(&self) -> Result<SocketAddr, io::Error> {
+        self.inner.local_addr()
+    }
+
+    #[inline]
+    pub fn peer_addr(&self) -> Result<SocketAddr, io::Error> {
+        self.peer
+            .ok_or_else(|| io::Error::new(ErrorKind::NotConnected, "socket is not connected"))
+    }
+
+    #[inline]
+    pub fn join_multicast_v6(
+        &mut self,
+        multiaddr: &Ipv6Addr,
+        interface: &Interface,
+    ) -> Result<(), io::Error> {
+        self.inner.join_multicast_v6(multiaddr, interface.index)
+    }
+
+    #[inline]
+    pub fn leave_multicast_v6(
+        &mut self,
+        multiaddr: &Ipv6Addr,
+        interface: &Interface,
+    ) -> Result<(), io::Error> {
+        self.inner.leave_multicast_v6(multiaddr, interface.index)
+    }
+
+    #[inline]
+    pub fn join_multicast_v4(
+        &mut self,
+        multiaddr: &Ipv4Addr,
+        interface: &Interface,
+    ) -> Result<(), io::Error> {
+        self.membership_v4(libc::IP_ADD_MEMBERSHIP, multiaddr, interface)
+    }
+
+    #[inline]
+    pub fn leave_multicast_v4(
+        &mut self,
+        multiaddr: &Ipv4Addr,
+        interface: &Interface,
+    ) -> Result<(), io::Error> {
+        self.membership_v4(libc::IP_DROP_MEMBERSHIP, multiaddr, interface)
+    }
+
+    /// `std` selects the interface of IPv4 memberships by address, Linux also takes an index.
+    fn membership_v4(
+        &mut self,
+        option: libc::c_int,
+        multiaddr: &Ipv4Addr,
+        interface: &Interface,
+    ) -> Result<(), io::Error> {
+        let request = IpMreqn {
+            imr_multiaddr: libc::in_addr {
+                s_addr: u32::from(*multiaddr).to_be(),
+            },
+            imr_address: libc::in_addr { s_addr: 0 },
+            imr_ifindex: interface.index as libc::c_int,
+        };
+
+        let error = unsafe {
+            libc::setsockopt(
+                self.inner.as_raw_fd(),
+                libc::IPPROTO_IP,
+                option,
+                &request as *const IpMreqn as *const _,
+                mem::size_of::<IpMreqn>() as libc::socklen_t,
+            )
+        };
+        if error != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_broadcast(&mut self, broadcast: bool) -> Result<(), io::Error> {
+        self.inner.set_broadcast(broadcast)
+    }
+
+    pub fn set_multicast_loop(&mut self, multicast_loop: bool) -> Result<(), io::Error> {
+        match self.inner.local_addr()? {
+            SocketAddr::V4(..) => self.inner.set_multicast_loop_v4(multicast_loop),
+            SocketAddr::V6(..) => self.inner.set_multicast_loop_v6(multicast_loop),
+        }
+    }
+
+    pub fn set_multicast_hops(&mut self, hops: u8) -> Result<(), io::Error> {
+        match self.inner.local_addr()? {
+            SocketAddr::V4(..) => self.inner.set_multicast_ttl_v4(u32::from(hops)),
+            // `std` has no setter for IPv6.
+            SocketAddr::V6(..) => {
+                let hops = libc::c_int::from(hops);
+                let error = unsafe {
+                    libc::setsockopt(
+                        self.inner.as_raw_fd(),
+                        libc::IPPROTO_IPV6,
+                        libc::IPV6_MULTICAST_HOPS,
+                        &hops as *const libc::c_int as *const _,
+                        mem::size_of::<libc::c_int>() as libc::socklen_t,
+                    )
+                };
+                if error != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    #[inline]
+    pub fn set_nonblocking(&mut self, nonblocking: bool) -> Result<(), io::Error> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+}
+
+impl AsRawFd for UdpSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl<'a> RecvBuf<'a> {
+    #[inline]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl<'a> Deref for RecvBuf<'a> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.data
+    }
+}
+
+/// `struct ip_mreqn` of Linux.
+#[repr(C)]
+struct IpMreqn {
+    imr_multiaddr: libc::in_addr,
+    imr_address: libc::in_addr,
+    imr_ifindex: libc::c_int,
+}
+
+fn raw_socket_addr(addr: &SocketAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+
+    let len = match *addr {
+        SocketAddr::V4(ref addr) => {
+            let raw = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in) };
+            raw.sin_family = libc::AF_INET as libc::sa_family_t;
+            raw.sin_port = addr.port().to_be();
+            raw.sin_addr = libc::in_addr {
+                s_addr: u32::from(*addr.ip()).to_be(),
+            };
+            mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(ref addr) => {
+            let raw = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in6) };
+            raw.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            raw.sin6_port = addr.port().to_be();
+            raw.sin6_addr.s6_addr = addr.ip().octets();
+            raw.sin6_flowinfo = addr.flowinfo();
+            raw.sin6_scope_id = addr.scope_id();
+            mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+
+    (storage, len as libc::socklen_t)
+}
+
+fn socket_addr(storage: &libc::sockaddr_storage) -> Result<SocketAddr, io::Error> {
+    match storage.ss_family as libc::c_int {
+        libc::AF_INET => {
+            let addr = unsafe { &*(storage as *const _ as *const libc::sockaddr_in) };
+            let ip = Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr));
+            Ok(SocketAddrV4::new(ip, u16::from_be(addr.sin_port)).into())
+        }
+        libc::AF_INET6 => {
+            let addr = unsafe { &*(storage as *const _ as *const libc::sockaddr_in6) };
+            let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+            let port = u16::from_be(addr.sin6_port);
+            Ok(SocketAddrV6::new(ip, port, addr.sin6_flowinfo, addr.sin6_scope_id).into())
+        }
+        _ => Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "unsupported address family",
+        )),
+    }
+}
+
+/// A network interface, identified by its index.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Interface {
+    index: u32,
+}
+
+impl Interface {
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn name(&self) -> Result<String, io::Error> {
+        let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+        if unsafe { libc::if_indextoname(self.index, name.as_mut_ptr()) }.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+        Ok(name.to_string_lossy().into_owned())
+    }
+
+    /// `getifaddrs` lacks the MTU, it is queried with `SIOCGIFMTU`.
+    pub fn mtu(&self) -> Result<u32, io::Error> {
+        let mut request: IfReqMtu = unsafe { mem::zeroed() };
+        let name = self.name()?;
+        // Names are shorter than `IF_NAMESIZE`, keeping the terminating zero.
+        for (raw, &byte) in request.ifr_name.iter_mut().zip(name.as_bytes()) {
+            *raw = byte as libc::c_char;
+        }
+
+        let socket = raw_socket(libc::AF_INET, libc::SOCK_DGRAM, 0)?;
+        let request_ptr = &mut request as *mut IfReqMtu;
+        if unsafe { libc::ioctl(socket.as_raw_fd(), SIOCGIFMTU, request_ptr) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(request.ifr_mtu as u32)
+    }
+
+    pub fn link_address(&self) -> Result<Vec<u8>, io::Error> {
+        let name = self.name()?;
+        let mut address = Vec::new();
+
+        each_ifaddr(|entry| {
+            let addr = match unsafe { entry.ifa_addr.as_ref() } {
+                Some(addr) if addr.sa_family as libc::c_int == libc::AF_PACKET => addr,
+                _ => return,
+            };
+            if entry_name(entry) == name {
+                let addr = unsafe { &*(addr as *const _ as *const libc::sockaddr_ll) };
+                address = addr.sll_addr[..addr.sll_halen as usize].to_vec();
+            }
+        })?;
+
+        Ok(address)
+    }
+
+    /// `getifaddrs` lacks the address flags, the addresses are dumped over netlink.
+    pub fn ipv6_addrs(&self) -> Result<Vec<net::InterfaceAddr>, io::Error> {
+        Ok(self
+            .inet6_entries()?
+            .into_iter()
+            .map(|(addr, _)| addr)
+            .collect())
+    }
+
+    /// The addresses of an `RTM_GETADDR` dump with their prefix length.
+    fn inet6_entries(&self) -> Result<Vec<(net::InterfaceAddr, u8)>, io::Error> {
+        let socket = netlink_socket()?;
+
+        let request = DumpRequest {
+            header: NlMsgHdr {
+                nlmsg_len: mem::size_of::<DumpRequest>() as u32,
+                nlmsg_type: RTM_GETADDR,
+                nlmsg_flags: NLM_F_REQUEST | NLM_F_DUMP,
+                nlmsg_seq: 1,
+                nlmsg_pid: 0,
+            },
+            message: IfAddrMsg {
+                ifa_family: libc::AF_INET6 as u8,
+                ifa_prefixlen: 0,
+                ifa_flags: 0,
+                ifa_scope: 0,
+                ifa_index: 0,
+            },
+        };
+
+        let sent = unsafe {
+            libc::send(
+                socket.as_raw_fd(),
+                &request as *const DumpRequest as *const _,
+                mem::size_of::<DumpRequest>(),
+                0,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut addrs = Vec::new();
+        let mut buf = Vec::new();
+
+        // The dump spans several datagrams of messages, until `NLMSG_DONE`.
+        loop {
+            // Peeks the length first, the kernel decides how large a datagram of the dump is.
+            let len = unsafe {
+                libc::recv(
+                    socket.as_raw_fd(),
+                    buf.as_mut_ptr() as *mut _,
+                    0,
+                    libc::MSG_PEEK | libc::MSG_TRUNC,
+                )
+            };
+            if len < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            buf.resize(len as usize, 0);
+
+            let received =
+                unsafe { libc::recv(socket.as_raw_fd(), buf.as_mut_ptr() as *mut _, buf.len(), 0) };
+            if received < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut messages = &buf[..received as usize];
+            while !messages.is_empty() {
+                if messages.len() < mem::size_of::<NlMsgHdr>() {
+                    return Err(malformed());
+                }
+                let header = unsafe { ptr::read_unaligned(messages.as_ptr() as *const NlMsgHdr) };
+                let len = header.nlmsg_len as usize;
+                if len < mem::size_of::<NlMsgHdr>() || len > messages.len() {
+                    return Err(malformed());
+                }
+                let payload = &messages[mem::size_of::<NlMsgHdr>()..len];
+
+                match header.nlmsg_type {
+                    NLMSG_DONE => return Ok(addrs),
+                    NLMSG_ERROR => {
+                        if payload.len() < mem::size_of::<i32>() {
+                            return Err(malformed());
+                        }
+                        let error = unsafe { ptr::read_unaligned(payload.as_ptr() as *const i32) };
+                        return Err(io::Error::from_raw_os_error(-error));
+                    }
+                    RTM_NEWADDR => {
+                        if let Some(entry) = self.inet6_entry(payload)? {
+                            addrs.push(entry);
+                        }
+                    }
+                    _ => {}
+                }
+
+                messages = &messages[netlink_align(len).min(messages.len())..];
+            }
+        }
+    }
+
+    /// Parses an `RTM_NEWADDR` message, returning `None` if it belongs to another interface.
+    fn inet6_entry(&self, payload: &[u8]) -> Result<Option<(net::InterfaceAddr, u8)>, io::Error> {
+        if payload.len() < mem::size_of::<IfAddrMsg>() {
+            return Err(malformed());
+        }
+        let message = unsafe { ptr::read_unaligned(payload.as_ptr() as *const IfAddrMsg) };
+        if message.ifa_index != self.index || message.ifa_family != libc::AF_INET6 as u8 {
+            return Ok(None);
+        }
+
+        let mut addr = None;
+        let mut raw_flags = u32::from(message.ifa_flags);
+
+        let mut attributes = &payload[mem::size_of::<IfAddrMsg>()..];
+        while attributes.len() >= mem::size_of::<RtAttr>() {
+            let attribute = unsafe { ptr::read_unaligned(attributes.as_ptr() as *const RtAttr) };
+            let len = attribute.rta_len as usize;
+            if len < mem::size_of::<RtAttr>() || len > attributes.len() {
+                return Err(malformed());
+            }
+            let data = &attributes[mem::size_of::<RtAttr>()..len];
+
+            match attribute.rta_type {
+                IFA_ADDRESS if data.len() == 16 => {
+                    let mut octets = [0; 16];
+                    octets.copy_from_slice(data);
+                    addr = Some(Ipv6Addr::from(octets));
+                }
+                // The flags of the message lack the newer ones.
+                IFA_FLAGS if data.len() == mem::size_of::<u32>() => {
+                    raw_flags = unsafe { ptr::read_unaligned(data.as_ptr() as *const u32) };
+                }
+                _ => {}
+            }
+
+            attributes = &attributes[netlink_align(len).min(attributes.len())..];
+        }
+
+        let addr = addr.ok_or_else(malformed)?;
+
+        let scope = match message.ifa_scope {
+            RT_SCOPE_HOST => net::Ipv6Scope::Host,
+            RT_SCOPE_LINK => net::Ipv6Scope::Link,
+            RT_SCOPE_SITE => net::Ipv6Scope::Site,
+            _ => net::Ipv6Scope::Global,
+        };
+
+        let mut flags = net::Ipv6AddrFlags::empty();
+        if raw_flags & IFA_F_TENTATIVE != 0 {
+            flags = flags | net::Ipv6AddrFlags::TENTATIVE;
+        }
+        if raw_flags & IFA_F_DEPRECATED != 0 {
+            flags = flags | net::Ipv6AddrFlags::DEPRECATED;
+        }
+
+        let addr = net::InterfaceAddr { addr, scope, flags };
+        Ok(Some((addr, message.ifa_prefixlen)))
+    }
+
+    /// Sends `RTM_NEWADDR` over netlink. Without `TENTATIVE` duplicate address detection is
+    /// skipped, Linux has no anycast flag.
+    pub fn add_ipv6_addr(
+        &self,
+        addr: &Ipv6Addr,
+        prefix_len: u8,
+        flags: net::Ipv6AddrFlags,
+    ) -> Result<(), io::Error> {
+        let supported = net::Ipv6AddrFlags::TENTATIVE;
+        if flags | supported != supported {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "unsupported address flags",
+            ));
+        }
+
+        let ifa_flags = if flags.contains(net::Ipv6AddrFlags::TENTATIVE) {
+            0
+        } else {
+            IFA_F_NODAD as u8
+        };
+
+        self.change_ipv6_addr(
+            RTM_NEWADDR,
+            NLM_F_CREATE | NLM_F_EXCL,
+            addr,
+            prefix_len,
+            ifa_flags,
+        )
+    }
+
+    /// Sends `RTM_DELADDR` over netlink, with the prefix length the address was added with.
+    pub fn remove_ipv6_addr(&self, addr: &Ipv6Addr) -> Result<(), io::Error> {
+        let prefix_len = self
+            .inet6_entries()?
+            .into_iter()
+            .find(|(entry, _)| entry.addr == *addr)
+            .map(|(_, prefix_len)| prefix_len)
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::EADDRNOTAVAIL))?;
+
+        self.change_ipv6_addr(RTM_DELADDR, 0, addr, prefix_len, 0)
+    }
+
+    /// Sends an address request to the kernel and waits for its acknowledgement.
+    fn change_ipv6_addr(
+        &self,
+        kind: u16,
+        request_flags: u16,
+        addr: &Ipv6Addr,
+        prefix_len: u8,
+        ifa_flags: u8,
+    ) -> Result<(), io::Error> {
+        let socket = netlink_socket()?;
+
+        let request = AddrRequest {
+            header: NlMsgHdr {
+                nlmsg_len: mem::size_of::<AddrRequest>() as u32,
+                nlmsg_type: kind,
+                nlmsg_flags: NLM_F_REQUEST | NLM_F_ACK | request_flags,
+                nlmsg_seq: 1,
+                nlmsg_pid: 0,
+            },
+            message: IfAddrMsg {
+                ifa_family: libc::AF_INET6 as u8,
+                ifa_prefixlen: prefix_len,
+                ifa_flags,
+                ifa_scope: 0,
+                ifa_index: self.index,
+            },
+            attribute: RtAttr {
+                rta_len: (mem::size_of::<RtAttr>() + 16) as u16,
+                rta_type: IFA_ADDRESS,
+            },
+            addr: addr.octets(),
+        };
+
+        let sent = unsafe {
+            libc::send(
+                socket.as_raw_fd(),
+                &request as *const AddrRequest as *const _,
+                mem::size_of::<AddrRequest>(),
+                0,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // The acknowledgement echoes the request, which is cut off.
+        let mut ack: NlMsgErr = unsafe { mem::zeroed() };
+        let received = unsafe {
+            libc::recv(
+                socket.as_raw_fd(),
+                &mut ack as *mut NlMsgErr as *mut _,
+                mem::size_of::<NlMsgErr>(),
+                0,
+            )
+        };
+        if received < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        if (received as usize) < mem::size_of::<NlMsgErr>() || ack.header.nlmsg_type != NLMSG_ERROR
+        {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "unexpected netlink response",
+            ));
+        }
+        match ack.error {
+            0 => Ok(()),
+            error => Err(io::Error::from_raw_os_error(-error)),
+        }
+    }
+
+    pub fn is_up(&self) -> Result<bool, io::Error> {
+        let name = self.name()?;
+        let mut up = false;
+
+        each_ifaddr(|entry| {
+            if entry_name(entry) == name {
+                up |= entry.ifa_flags & libc::IFF_UP as libc::c_uint != 0;
+            }
+        })?;
+
+        Ok(up)
+    }
+}
+
+// Scopes of `linux/rtnetlink.h`.
+const RT_SCOPE_SITE: u8 = 200;
+const RT_SCOPE_LINK: u8 = 253;
+const RT_SCOPE_HOST: u8 = 254;
+
+// Address flags of `linux/if_addr.h`.
+const IFA_F_NODAD: u32 = 0x02;
+const IFA_F_DEPRECATED: u32 = 0x20;
+const IFA_F_TENTATIVE: u32 = 0x40;
+
+// Netlink message types, flags and attributes of `linux/netlink.h` and `linux/rtnetlink.h`.
+const NLMSG_ERROR: u16 = 0x02;
+const NLMSG_DONE: u16 = 0x03;
+const RTM_NEWADDR: u16 = 20;
+const RTM_DELADDR: u16 = 21;
+const RTM_GETADDR: u16 = 22;
+const NLM_F_REQUEST: u16 = 0x01;
+const NLM_F_ACK: u16 = 0x04;
+const NLM_F_EXCL: u16 = 0x200;
+const NLM_F_DUMP: u16 = 0x300;
+const NLM_F_CREATE: u16 = 0x400;
+const IFA_ADDRESS: u16 = 1;
+const IFA_FLAGS: u16 = 8;
+
+// `linux/sockios.h`
+const SIOCGIFMTU: libc::c_ulong = 0x8921;
+
+/// `struct ifreq` with the MTU of its union.
+#[repr(C)]
+struct IfReqMtu {
+    ifr_name: [libc::c_char; libc::IF_NAMESIZE],
+    ifr_mtu: libc::c_int,
+    // The union is as large as `struct ifmap`.
+    _padding: [u8; 20],
+}
+
+/// `struct nlmsghdr`
+#[repr(C)]
+struct NlMsgHdr {
+    nlmsg_len: u32,
+    nlmsg_type: u16,
+    nlmsg_flags: u16,
+    nlmsg_seq: u32,
+    nlmsg_pid: u32,
+}
+
+/// `struct ifaddrmsg`
+#[repr(C)]
+struct IfAddrMsg {
+    ifa_family: u8,
+    ifa_prefixlen: u8,
+    ifa_flags: u8,
+    ifa_scope: u8,
+    ifa_index: u32,
+}
+
+/// `struct rtattr`
+#[repr(C)]
+struct RtAttr {
+    rta_len: u16,
+    rta_type: u16,
+}
+
+/// An address request with its only attribute, all parts are aligned without padding.
+#[repr(C)]
+struct AddrRequest {
+    header: NlMsgHdr,
+    message: IfAddrMsg,
+    attribute: RtAttr,
+    addr: [u8; 16],
+}
+
+/// A request to dump the addresses of a family, without attributes.
+#[repr(C)]
+struct DumpRequest {
+    header: NlMsgHdr,
+    message: IfAddrMsg,
+}
+
+/// `struct nlmsgerr` without the echoed request.
+#[repr(C)]
+struct NlMsgErr {
+    header: NlMsgHdr,
+    error: i32,
+}
+
+/// Lists the interfaces with an entry in `getifaddrs`, which includes those without address.
+pub fn interfaces() -> Result<Vec<Interface>, io::Error> {
+    let mut interfaces = Vec::new();
+
+    each_ifaddr(|entry| {
+        let index = unsafe { libc::if_nametoindex(entry.ifa_name) };
+        let interface = Interface { index };
+        if index != 0 && !interfaces.contains(&interface) {
+            interfaces.push(interface);
+        }
+    })?;
+
+    interfaces.sort_by_key(|interface| interface.index);
+    Ok(interfaces)
+}
+
+/// Opens a socket, which is closed when the file is dropped.
+fn raw_socket(
+    domain: libc::c_int,
+    kind: libc::c_int,
+    protocol: libc::c_int,
+) -> Result<fs::File, io::Error> {
+    let fd = unsafe { libc::socket(domain, kind | libc::SOCK_CLOEXEC, protocol) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { fs::File::from_raw_fd(fd) })
+}
+
+fn netlink_socket() -> Result<fs::File, io::Error> {
+    raw_socket(libc::AF_NETLINK, libc::SOCK_RAW, libc::NETLINK_ROUTE)
+}
+
+/// `NLMSG_ALIGN` and `RTA_ALIGN`, both align to 4 bytes.
+fn netlink_align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "malformed netlink message")
+}
+
+/// Runs `f` on every entry of `getifaddrs`.
+fn each_ifaddr<F>(mut f: F) -> Result<(), io::Error>
+where
+    F: FnMut(&libc::ifaddrs),
+{
+    let mut list = ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut list) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut next = list;
+    while let Some(entry) = unsafe { next.as_ref() } {
+        f(entry);
+        next = entry.ifa_next;
+    }
+
+    unsafe { libc::freeifaddrs(list) };
+    Ok(())
+}
+
+fn entry_name(entry: &libc::ifaddrs) -> Cow<str> {
+    unsafe { CStr::from_ptr(entry.ifa_name) }.to_string_lossy()
+}
+
+pub fn eui48(interface: &Interface) -> Result<net::Eui48, io::Error> {
+    let address = interface.link_address()?;
+
+    // Loopback has an all-zero address.
+    if address.len() != 6 || address.iter().all(|&byte| byte == 0) {
+        return Err(io::Error::new(
+            ErrorKind::AddrNotAvailable,
+            "interface has no MAC address",
+        ));
+    }
+
+    let mut eui48 = [0; 6];
+    eui48.copy_from_slice(&address);
+    Ok(net::Eui48(eui48))
+}
+
+pub fn eui64(interface: &Interface) -> Result<net::Eui64, io::Error> {
+    let mac_bytes = eui48(interface)?.0;
+
+    let mut eui64 = [0xFF; 8];
+
+    // Split the MAC address, inserting `FF FE`
+    eui64[..3].copy_from_slice(&mac_bytes[..3]);
+    eui64[4] = 0xFE;
+    eui64[5..].copy_from_slice(&mac_bytes[3..]);
+
+    // invert the universal/local (U/L) flag (bit 7) in the OUI portion of the address
+    eui64[0] ^= 0x02;
+
+    Ok(net::Eui64(eui64))
+}
+
+/// Non-blocking UDP socket woken by the reactor thread.
+pub struct AsyncUdpSocket {
+    inner: std_net::UdpSocket,
+}
+
+impl AsyncUdpSocket {
+    pub fn bind(local: SocketAddr) -> Result<Self, io::Error> {
+        let inner = std_net::UdpSocket::bind(local)?;
+        inner.set_nonblocking(true)?;
+        Ok(AsyncUdpSocket { inner })
+    }
+
+    #[inline]
+    pub fn local_addr(&self) -> Result<SocketAddr, io::Error> {
+        self.inner.local_addr()
+    }
+
+    pub fn poll_recv_from(
+        &mut self,
+        lw: &LocalWaker,
+        buf: &mut [u8],
+    ) -> task::Poll<Result<(usize, SocketAddr), io::Error>> {
+        match self.inner.recv_from(buf) {
+            Err(ref error) if error.kind() == ErrorKind::WouldBlock => {
+                // `poll` is level-triggered, a datagram which arrived meanwhile wakes right away.
+                Reactor::get().register(
+                    self.inner.as_raw_fd(),
+                    libc::POLLIN,
+                    lw.clone().into_waker(),
+                );
+                task::Poll::Pending
+            }
+            result => task::Poll::Ready(result),
+        }
+    }
+
+    pub fn poll_send_to(
+        &mut self,
+        lw: &LocalWaker,
+        buf: &[u8],
+        addr: SocketAddr,
+    ) -> task::Poll<Result<usize, io::Error>> {
+        match self.inner.send_to(buf, addr) {
+            Err(ref error) if error.kind() == ErrorKind::WouldBlock => {
+                Reactor::get().register(
+                    self.inner.as_raw_fd(),
+                    libc::POLLOUT,
+                    lw.clone().into_waker(),
+                );
+                task::Poll::Pending
+            }
+            result => task::Poll::Ready(result),
+        }
+    }
+}
+
+impl Drop for AsyncUdpSocket {
+    fn drop(&mut self) {
+        // A pending future left its waker behind, which must not be woken for a reused fd.
+        Reactor::get().deregister(self.inner.as_raw_fd());
+    }
+}
+
+/// Readiness polling with epoll.
+pub struct Poll {
+    epoll: RawFd,
+    raw_events: Vec<libc::epoll_event>,
+}
+
+impl Poll {
+    pub fn new() -> Result<Poll, io::Error> {
+        let epoll = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+        if epoll < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Poll {
+            epoll,
+            raw_events: Vec::new(),
+        })
+    }
+
+    pub fn register(
+        &mut self,
+        socket: &mut UdpSocket,
+        token: net::Token,
+        interest: net::Interest,
+    ) -> Result<(), io::Error> {
+        self.control(libc::EPOLL_CTL_ADD, socket, token, interest)
+    }
+
+    pub fn reregister(
+        &mut self,
+        socket: &mut UdpSocket,
+        token: net::Token,
+        interest: net::Interest,
+    ) -> Result<(), io::Error> {
+        self.control(libc::EPOLL_CTL_MOD, socket, token, interest)
+    }
+
+    pub fn deregister(&mut self, socket: &mut UdpSocket) -> Result<(), io::Error> {
+        // Linux before 2.6.9 requires an event even though it is ignored.
+        let mut event = libc::epoll_event { events: 0, u64: 0 };
+        let error = unsafe {
+            libc::epoll_ctl(
+                self.epoll,
+                libc::EPOLL_CTL_DEL,
+                socket.as_raw_fd(),
+                &mut event,
+            )
+        };
+        if error < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn control(
+        &mut self,
+        op: libc::c_int,
+        socket: &mut UdpSocket,
+        token: net::Token,
+        interest: net::Interest,
+    ) -> Result<(), io::Error> {
+        let mut events = 0;
+        if interest.is_readable() {
+            events |= libc::EPOLLIN;
+        }
+        if interest.is_writable() {
+            events |= libc::EPOLLOUT;
+        }
+
+        let mut event = libc::epoll_event {
+            events: events as u32,
+            u64: token.0 as u64,
+        };
+
+        let error = unsafe { libc::epoll_ctl(self.epoll, op, socket.as_raw_fd(), &mut event) };
+        if error < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub fn poll(
+        &mut self,
+        events: &mut Vec<net::Event>,
+        capacity: usize,
+        timeout: Option<Duration>,
+    ) -> Result<(), io::Error> {
+        let timeout = timeout.map_or(-1, timeout_millis);
+        self.raw_events
+            .resize(capacity, libc::epoll_event { events: 0, u64: 0 });
+
+        let ready = unsafe {
+            libc::epoll_wait(
+                self.epoll,
+                self.raw_events.as_mut_ptr(),
+                self.raw_events.len() as _,
+                timeout,
+            )
+        };
+
+        if ready < 0 {
+            let error = io::Error::last_os_error();
+            // Treated like a timeout.
+            if error.kind() == ErrorKind::Interrupted {
+                return Ok(());
+            }
+            return Err(error);
+        }
+
+        for raw_event in &self.raw_events[..ready as usize] {
+            let flags = raw_event.events as libc::c_int;
+            let errors = flags & (libc::EPOLLERR | libc::EPOLLHUP) != 0;
+            let readable = errors || flags & libc::EPOLLIN != 0;
+            let writable = errors || flags & libc::EPOLLOUT != 0;
+
+            let readiness = match (readable, writable) {
+                (true, true) => net::Interest::READABLE | net::Interest::WRITABLE,
+                (true, false) => net::Interest::READABLE,
+                (false, true) => net::Interest::WRITABLE,
+                (false, false) => continue,
+            };
+
+            let token = net::Token(raw_event.u64 as usize);
+            events.push(net::Event::new(token, readiness));
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Poll {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.epoll);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Reactor thread waking futures once their file descriptor is ready or their timer expired.
+
+use std::future::Future;
+use std::io;
+use std::os::unix::io::RawFd;
+use std::pin::Pin;
+use std::sync::{Mutex, Once};
+use std::task::{LocalWaker, Poll, Waker};
+use std::thread as std_thread;
+use std::time::{Duration, Instant};
+
+pub(crate) struct Reactor {
+    state: Mutex<State>,
+    // Write end of the pipe which interrupts `poll`.
+    notify: RawFd,
+}
+
+struct State {
+    io: Vec<(RawFd, libc::c_short, Waker)>,
+    timers: Vec<(u64, Instant, Waker)>,
+    next_timer: u64,
+}
+
+static START: Once = Once::new();
+static mut REACTOR: *const Reactor = 0 as *const Reactor;
+
+impl Reactor {
+    /// Starts the reactor thread on first use.
+    pub(crate) fn get() -> &'static Reactor {
+        START.call_once(|| unsafe {
+            let reactor = Reactor::start().expect("failed to start the reactor");
+            REACTOR = Box::into_raw(Box::new(reactor));
+        });
+        unsafe { &*REACTOR }
+    }
+
+    fn start() -> io::Result<Reactor> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK | libc::O_CLOEXEC) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let (wakeup, notify) = (fds[0], fds[1]);
+
+        std_thread::Builder::new()
+            .name("os_glue_reactor".into())
+            .spawn(move || Reactor::get().run(wakeup))?;
+
+        Ok(Reactor {
+            state: Mutex::new(State {
+                io: Vec::new(),
+                timers: Vec::new(),
+                next_timer: 0,
+            }),
+            notify,
+        })
+    }
+
+    /// Wakes `waker` once `fd` is ready for `events`, or an error occurred on it.
+    ///
+    /// Replaces the waker of an earlier registration of `fd` for the same `events`.
+    pub(crate) fn register(&self, fd: RawFd, events: libc::c_short, waker: Waker) {
+        let mut state = self.state.lock().unwrap();
+
+        let registered = state
+            .io
+            .iter_mut()
+            .find(|registration| registration.0 == fd && registration.1 == events);
+        match registered {
+            Some(registration) => registration.2 = waker,
+            None => {
+                state.io.push((fd, events, waker));
+                // Only a new file descriptor has to be added to the running poll.
+                self.notify();
+            }
+        }
+    }
+
+    /// Removes every registration of `fd`, before it is closed.
+    pub(crate) fn deregister(&self, fd: RawFd) {
+        self.state
+            .lock()
+            .unwrap()
+            .io
+            .retain(|&(registered, _, _)| registered != fd);
+    }
+
+    /// Wakes `waker` at `deadline`, unless the returned timer is removed before.
+    pub(crate) fn add_timer(&self, deadline: Instant, waker: Waker) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let timer = state.next_timer;
+        state.next_timer += 1;
+        state.timers.push((timer, deadline, waker));
+        self.notify();
+        timer
+    }
+
+    /// Replaces the waker of `timer`, which is woken right away if the timer already fired.
+    pub(crate) fn replace_timer(&self, timer: u64, waker: Waker) {
+        let mut state = self.state.lock().unwrap();
+        match state
+            .timers
+            .iter_mut()
+            .find(|registered| registered.0 == timer)
+        {
+            Some(registered) => registered.2 = waker,
+            None => waker.wake(),
+        }
+    }
+
+    pub(crate) fn remove_timer(&self, timer: u64) {
+        self.state
+            .lock()
+            .unwrap()
+            .timers
+            .retain(|&(registered, _, _)| registered != timer);
+    }
+
+    fn notify(&self) {
+        // A full pipe already interrupts the reactor.
+        unsafe {
+            libc::write(self.notify, &1u8 as *const u8 as *const _, 1);
+        }
+    }
+
+    fn run(&self, wakeup: RawFd) {
+        let mut fds = Vec::new();
+        let mut buf = [0u8; 64];
+
+        loop {
+            let timeout = {
+                let state = self.state.lock().unwrap();
+
+                fds.clear();
+                fds.push(libc::pollfd {
+                    fd: wakeup,
+                    events: libc::POLLIN,
+                    revents: 0,
+                });
+                fds.extend(state.io.iter().map(|&(fd, events, _)| libc::pollfd {
+                    fd,
+                    events,
+                    revents: 0,
+                }));
+
+                let now = Instant::now();
+                state
+                    .timers
+                    .iter()
+                    .map(|&(_, deadline, _)| {
+                        if deadline > now {
+                            timeout_millis(deadline - now)
+                        } else {
+                            0
+                        }
+                    })
+                    .min()
+                    .unwrap_or(-1)
+            };
+
+            let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as _, timeout) };
+            if ready < 0 {
+                let error = io::Error::last_os_error();
+                assert_eq!(
+                    error.kind(),
+                    io::ErrorKind::Interrupted,
+                    "poll failed: {}",
+                    error
+                );
+                continue;
+            }
+
+            // Drain the notifications, the registrations are picked up by the next poll.
+            while unsafe { libc::read(wakeup, buf.as_mut_ptr() as *mut _, buf.len()) } > 0 {}
+
+            let mut state = self.state.lock().unwrap();
+
+            for pollfd in fds[1..].iter().filter(|pollfd| pollfd.revents != 0) {
+                // Also wakes registrations made during the poll, which is merely spurious.
+                state.io.retain(|&(fd, events, ref waker)| {
+                    let errors = libc::POLLERR | libc::POLLHUP | libc::POLLNVAL;
+                    if fd == pollfd.fd && pollfd.revents & (events | errors) != 0 {
+                        waker.wake();
+                        false
+                    } else {
+                        true
+                    }
+                });
+            }
+
+            let now = Instant::now();
+            state.timers.retain(|&(_, deadline, ref waker)| {
+                if deadline <= now {
+                    waker.wake();
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+}
+
+/// Rounds up, so that the reactor does not wake before the deadline.
+pub(super) fn timeout_millis(duration: Duration) -> libc::c_int {
+    let millis = duration.as_secs().saturating_mul(1000)
+        + u64::from((duration.subsec_nanos() + 999_999) / 1_000_000);
+    if millis > libc::c_int::max_value() as u64 {
+        libc::c_int::max_value()
+    } else {
+        millis as libc::c_int
+    }
+}
+
+/// Future completing at a deadline, woken by the reactor.
+pub struct Sleep {
+    deadline: Instant,
+    // The registered timer and its waker.
+    timer: Option<(u64, Waker)>,
+}
+
+impl Sleep {
+    pub fn new(duration: Duration) -> Sleep {
+        Sleep {
+            deadline: Instant::now() + duration,
+            timer: None,
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        let timer = match self.timer {
+            Some((_, ref waker)) if lw.will_wake_nonlocal(waker) => return Poll::Pending,
+            Some((timer, _)) => {
+                let waker = lw.clone().into_waker();
+                Reactor::get().replace_timer(timer, waker.clone());
+                (timer, waker)
+            }
+            None => {
+                let waker = lw.clone().into_waker();
+                let timer = Reactor::get().add_timer(self.deadline, waker.clone());
+                (timer, waker)
+            }
+        };
+
+        self.timer = Some(timer);
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some((timer, _)) = self.timer {
+            Reactor::get().remove_timer(timer);
+        }
+    }
+}
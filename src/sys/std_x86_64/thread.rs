@@ -0,0 +1,458 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread as std_thread;
+use std::time::{Duration, Instant};
+
+pub use std::panic::resume_unwind;
+pub use std::thread::{panicking, park, park_timeout, sleep, yield_now, LocalKey};
+
+use crate::thread;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<Inner>>> = RefCell::new(None);
+}
+
+/// A unique identifier for a running thread, its Linux thread id.
+///
+/// Linux reuses the identifier of an exited thread.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct ThreadId(u64);
+
+impl ThreadId {
+    fn current() -> ThreadId {
+        ThreadId(unsafe { libc::syscall(libc::SYS_gettid) } as u64)
+    }
+
+    #[inline]
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+/// State of a thread which the standard library does not provide.
+struct Inner {
+    // Only known once the thread runs.
+    id: Mutex<Option<ThreadId>>,
+    started: Condvar,
+    // Emulation of RIOT's thread flags.
+    flags: Mutex<thread::Flags>,
+    changed: Condvar,
+}
+
+impl Inner {
+    fn new(id: Option<ThreadId>) -> Self {
+        Inner {
+            id: Mutex::new(id),
+            started: Condvar::new(),
+            flags: Mutex::new(0),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Called by the thread itself before anything else.
+    fn start(&self) {
+        *self.id.lock().unwrap() = Some(ThreadId::current());
+        self.started.notify_all();
+    }
+}
+
+/// A handle to a thread
+#[derive(Clone)]
+pub struct Thread {
+    inner: std_thread::Thread,
+    state: Arc<Inner>,
+}
+
+impl Thread {
+    #[inline]
+    pub fn unpark(&self) {
+        self.inner.unpark()
+    }
+
+    /// Blocks until a just spawned thread runs and knows its id.
+    #[inline]
+    pub fn id(&self) -> ThreadId {
+        let mut id = self.state.id.lock().unwrap();
+        loop {
+            match *id {
+                Some(id) => return id,
+                None => id = self.state.started.wait(id).unwrap(),
+            }
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name()
+    }
+
+    #[inline]
+    pub fn set_flags(&self, mask: thread::Flags) {
+        let mut set = self.state.flags.lock().unwrap();
+        *set |= mask;
+        self.state.changed.notify_all();
+    }
+}
+
+/// The payload of a thread's panic.
+pub type Panic = Box<dyn Any + Send + 'static>;
+
+/// Runs `f`, returning its panic instead of unwinding further.
+#[inline]
+pub fn catch_unwind<F: FnOnce()>(f: F) -> Result<(), Panic> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+}
+
+pub struct JoinHandle<T = ()> {
+    inner: std_thread::JoinHandle<T>,
+    thread: Thread,
+    // Unused, only kept to hand it back.
+    stack: Option<thread::Stack>,
+}
+
+impl<T> JoinHandle<T> {
+    #[inline]
+    pub fn thread(&self) -> &Thread {
+        &self.thread
+    }
+
+    #[inline]
+    pub fn join(self) -> std_thread::Result<T> {
+        self.inner.join()
+    }
+
+    #[inline]
+    pub fn join_inner(self) -> Result<(), Panic> {
+        self.inner.join().map(|_| ())
+    }
+
+    pub fn join_stack(self) -> Option<thread::Stack> {
+        if let Err(panic) = self.inner.join() {
+            resume_unwind(panic)
+        }
+        self.stack
+    }
+
+    #[inline]
+    pub fn detach(self) {}
+
+    #[inline]
+    pub fn stack_high_water_mark(&self) -> Option<usize> {
+        None
+    }
+
+    /// Overflows hit the guard page of the standard library and abort the process.
+    #[inline]
+    pub fn check_stack(&self) -> Result<(), thread::StackOverflow> {
+        Ok(())
+    }
+}
+
+fn current_state() -> Arc<Inner> {
+    CURRENT.with(|state| {
+        state
+            .borrow_mut()
+            .get_or_insert_with(|| Arc::new(Inner::new(Some(ThreadId::current()))))
+            .clone()
+    })
+}
+
+#[inline]
+pub fn current() -> Thread {
+    Thread {
+        inner: std_thread::current(),
+        state: current_state(),
+    }
+}
+
+#[inline]
+pub fn wait_flags_any(mask: thread::Flags, timeout: Option<Duration>) -> Option<thread::Flags> {
+    wait_flags(mask, timeout, |set| set & mask != 0)
+}
+
+#[inline]
+pub fn wait_flags_all(mask: thread::Flags, timeout: Option<Duration>) -> Option<thread::Flags> {
+    wait_flags(mask, timeout, |set| set & mask == mask)
+}
+
+#[inline]
+pub fn clear_flags(mask: thread::Flags) -> thread::Flags {
+    let state = current_state();
+    let mut set = state.flags.lock().unwrap();
+    let cleared = *set & mask;
+    *set &= !mask;
+    cleared
+}
+
+fn wait_flags<F>(mask: thread::Flags, timeout: Option<Duration>, ready: F) -> Option<thread::Flags>
+where
+    F: Fn(thread::Flags) -> bool,
+{
+    let state = current_state();
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    let mut set = state.flags.lock().unwrap();
+    while !ready(*set) {
+        set = match deadline {
+            None => state.changed.wait(set).unwrap(),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                state.changed.wait_timeout(set, deadline - now).unwrap().0
+            }
+        };
+    }
+
+    let received = *set & mask;
+    *set &= !mask;
+    Some(received)
+}
+
+pub fn stack_remaining() -> Option<usize> {
+    let marker = 0u8;
+    unsafe {
+        let mut attr = mem::zeroed();
+        if libc::pthread_getattr_np(libc::pthread_self(), &mut attr) != 0 {
+            return None;
+        }
+
+        let mut stack_start = ptr::null_mut();
+        let mut stack_size = 0;
+        let error = libc::pthread_attr_getstack(&attr, &mut stack_start, &mut stack_size);
+        libc::pthread_attr_destroy(&mut attr);
+
+        if error != 0 {
+            return None;
+        }
+        Some((&marker as *const u8 as usize).saturating_sub(stack_start as usize))
+    }
+}
+
+pub fn list() -> io::Result<Vec<thread::ThreadInfo>> {
+    let mut threads = Vec::new();
+
+    for entry in fs::read_dir("/proc/self/task")? {
+        let path = entry?.path();
+
+        let tid: u64 = match path.file_name().and_then(|tid| tid.to_str()) {
+            Some(tid) => tid.parse().map_err(|_| io::ErrorKind::InvalidData)?,
+            None => continue,
+        };
+
+        // The thread may have exited in the meantime.
+        let stat = match fs::read_to_string(path.join("stat")) {
+            Ok(stat) => stat,
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error),
+        };
+
+        threads.push(parse_stat(tid, &stat).ok_or(io::ErrorKind::InvalidData)?);
+    }
+
+    Ok(threads)
+}
+
+/// Parses `/proc/<pid>/task/<tid>/stat`, see proc(5).
+fn parse_stat(tid: u64, stat: &str) -> Option<thread::ThreadInfo> {
+    // The name is in parentheses and may contain spaces or parentheses itself.
+    let name_start = stat.find('(')? + 1;
+    let name_end = stat.rfind(')')?;
+    let name = &stat[name_start..name_end];
+
+    // Fields after the name, starting with field 3, the state.
+    let mut fields = stat[name_end + 1..].split_whitespace();
+
+    let status = match fields.next()? {
+        "R" => thread::ThreadStatus::Running,
+        "S" => thread::ThreadStatus::Sleeping,
+        "D" => thread::ThreadStatus::Blocked,
+        "T" | "t" | "Z" | "X" => thread::ThreadStatus::Stopped,
+        _ => thread::ThreadStatus::Unknown,
+    };
+
+    // Field 18
+    let priority = fields.nth(14)?.parse().ok()?;
+
+    Some(thread::ThreadInfo {
+        id: ThreadId(tid),
+        name: Some(name.into()),
+        priority,
+        status,
+        stack_size: None,
+        stack_used: None,
+    })
+}
+
+pub const PRIORITY_LEVELS: u8 = 16;
+pub const PRIORITY_MAIN: u8 = 8;
+
+pub struct Builder {
+    inner: std_thread::Builder,
+    priority: Option<thread::Priority>,
+    flags: thread::CreateFlags,
+    stack: Option<thread::Stack>,
+}
+
+/// Maps the priority onto the nice value of the calling thread.
+fn set_priority(priority: thread::Priority) -> Result<(), thread::SpawnError> {
+    let levels = i32::from(PRIORITY_LEVELS);
+    let main = i32::from(PRIORITY_MAIN);
+    let level = i32::from(priority.as_raw());
+
+    // MAIN is nice 0, the least urgent level 19 and the most urgent -20.
+    let nice = if level >= main {
+        (level - main) * 19 / (levels - 1 - main)
+    } else {
+        (level - main) * 20 / main
+    };
+
+    let error = unsafe {
+        // Linux applies the priority of PRIO_PROCESS to a single thread.
+        let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+        libc::setpriority(libc::PRIO_PROCESS as _, tid, nice)
+    };
+
+    if error == 0 {
+        return Ok(());
+    }
+
+    match io::Error::last_os_error().raw_os_error() {
+        Some(libc::EPERM) | Some(libc::EACCES) => Err(thread::SpawnError::PermissionDenied),
+        Some(code) => Err(thread::SpawnError::Os(code)),
+        None => Err(thread::SpawnError::Unknown),
+    }
+}
+
+fn spawn_error(error: io::Error) -> thread::SpawnError {
+    match error.raw_os_error() {
+        Some(libc::EAGAIN) => thread::SpawnError::TooManyThreads,
+        Some(libc::ENOMEM) => thread::SpawnError::OutOfMemory,
+        Some(code) => thread::SpawnError::Os(code),
+        None => thread::SpawnError::Unknown,
+    }
+}
+
+impl thread::BuilderExt for Builder {
+    type JoinHandle = thread::JoinHandle;
+    fn new() -> Self {
+        Builder {
+            inner: std_thread::Builder::new(),
+            priority: None,
+            flags: thread::CreateFlags::empty(),
+            stack: None,
+        }
+    }
+
+    fn name(self, name: &'static str) -> Self {
+        // Names may be given NUL-terminated for RIOT, which the standard library rejects.
+        Builder {
+            inner: self.inner.name(name.trim_end_matches('\0').into()),
+            ..self
+        }
+    }
+
+    fn stack_size(self, stack_size: i32) -> Self {
+        Builder {
+            inner: self.inner.stack_size(stack_size as _),
+            ..self
+        }
+    }
+
+    fn priority(mut self, priority: thread::Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    fn flags(mut self, flags: thread::CreateFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    fn stack(mut self, stack: thread::Stack) -> Self {
+        self.stack = Some(stack);
+        self
+    }
+
+    fn spawn<F>(self, f: F) -> Result<Self::JoinHandle, thread::SpawnError>
+    where
+        F: FnOnce() -> (),
+        F: Send + 'static,
+    {
+        let Builder {
+            inner,
+            priority,
+            flags,
+            stack,
+        } = self;
+        let sleeping = flags.contains(thread::CreateFlags::SLEEPING);
+
+        // The state is shared before the thread runs, so e.g. flags can be set through the
+        // handle right away.
+        let state = Arc::new(Inner::new(None));
+        let their_state = state.clone();
+
+        // The priority can only be applied by the thread itself, which reports back.
+        let (started, applied) = mpsc::sync_channel(1);
+
+        let main = move || {
+            their_state.start();
+            CURRENT.with(|current| *current.borrow_mut() = Some(their_state));
+
+            if let Some(priority) = priority {
+                let result = set_priority(priority);
+                let failed = result.is_err();
+                let _ = started.send(result);
+
+                if failed {
+                    return;
+                }
+            }
+            if sleeping {
+                park();
+            }
+            f()
+        };
+
+        let inner = inner.spawn(main).map_err(spawn_error)?;
+
+        // Only wait for the thread if it has to report whether the priority was applied.
+        if priority.is_some() {
+            match applied.recv() {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    let _ = inner.join();
+                    return Err(error);
+                }
+                // The thread exited without reporting, i.e. it panicked.
+                Err(_) => return Err(thread::SpawnError::Unknown),
+            }
+        }
+
+        Ok(JoinHandle {
+            thread: Thread {
+                inner: inner.thread().clone(),
+                state,
+            },
+            inner,
+            stack,
+        }
+        .into())
+    }
+}
+
+pub fn spawn<F, B>(f: F) -> B::JoinHandle
+where
+    F: FnOnce() -> (),
+    F: Send + 'static,
+    B: thread::BuilderExt,
+{
+    B::new().spawn(f).expect("thread spawn failed")
+}
@@ -0,0 +1,162 @@
+//! A minimal single-threaded executor for futures.
+//!
+//! The executor parks the thread while no task is ready, its wakers unpark it again. On RIOT the
+//! wakers are safe to use from interrupt context.
+//!
+//! # Examples
+//! ```
+//! #![feature(async_await, await_macro, futures_api)]
+//!
+//! use os_glue::task::Executor;
+//! use os_glue::time::{self, Duration};
+//!
+//! let mut executor = Executor::new();
+//!
+//! executor.spawn(async {
+//!     await!(time::sleep(Duration::from_millis(10)));
+//! });
+//!
+//! let answer = executor.block_on(async { 42 });
+//! assert_eq!(answer, 42);
+//!
+//! // Runs the spawned tasks to completion.
+//! executor.run();
+//! ```
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::task::{local_waker_from_nonlocal, Wake};
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{LocalWaker, Poll};
+
+use crate::thread::{self, Thread};
+
+/// Runs `future` to completion on the current thread.
+pub fn block_on<F>(future: F) -> F::Output
+where
+    F: Future,
+{
+    Executor::new().block_on(future)
+}
+
+/// Runs futures on the thread which created it.
+pub struct Executor {
+    tasks: Vec<Task>,
+}
+
+struct Task {
+    waker: Arc<TaskWaker>,
+    future: Pin<Box<dyn Future<Output = ()>>>,
+}
+
+struct TaskWaker {
+    woken: AtomicBool,
+    thread: Thread,
+}
+
+impl Executor {
+    pub fn new() -> Executor {
+        Executor { tasks: Vec::new() }
+    }
+
+    /// Queues a task, which is executed by [run] or while [block_on] waits.
+    ///
+    /// [run]: Executor::run
+    /// [block_on]: Executor::block_on
+    pub fn spawn<F>(&mut self, future: F)
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.tasks.push(Task {
+            waker: TaskWaker::new(),
+            future: Box::pinned(future),
+        });
+    }
+
+    /// Runs the spawned tasks until all of them completed.
+    pub fn run(&mut self) {
+        while !self.tasks.is_empty() {
+            if !self.poll_tasks() {
+                thread::park();
+            }
+        }
+    }
+
+    /// Runs `future` to completion, executing the spawned tasks meanwhile.
+    pub fn block_on<F>(&mut self, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        let mut future = Box::pinned(future);
+        let waker = TaskWaker::new();
+
+        loop {
+            let mut progress = false;
+
+            if waker.woken.swap(false, Ordering::Acquire) {
+                let local_waker = local_waker_from_nonlocal(waker.clone());
+                if let Poll::Ready(output) = future.as_mut().poll(&local_waker) {
+                    return output;
+                }
+                progress = true;
+            }
+
+            progress |= self.poll_tasks();
+
+            if !progress {
+                thread::park();
+            }
+        }
+    }
+
+    /// Polls every woken task once, returns whether any was woken.
+    fn poll_tasks(&mut self) -> bool {
+        let mut progress = false;
+        let mut index = 0;
+
+        while index < self.tasks.len() {
+            let task = &mut self.tasks[index];
+
+            if task.waker.woken.swap(false, Ordering::Acquire) {
+                progress = true;
+
+                let local_waker = local_waker_from_nonlocal(task.waker.clone());
+                if task.future.as_mut().poll(&local_waker).is_ready() {
+                    self.tasks.swap_remove(index);
+                    continue;
+                }
+            }
+
+            index += 1;
+        }
+
+        progress
+    }
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Executor::new()
+    }
+}
+
+impl TaskWaker {
+    /// Woken initially, so that the task is polled once.
+    fn new() -> Arc<TaskWaker> {
+        Arc::new(TaskWaker {
+            woken: AtomicBool::new(true),
+            thread: thread::current(),
+        })
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, Ordering::Release);
+        // The park token is kept until the executor parks, so no wakeup is lost.
+        arc_self.thread.unpark();
+    }
+}
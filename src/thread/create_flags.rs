@@ -0,0 +1,70 @@
+use core::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// Options for creating a thread, set with [`BuilderExt::flags`].
+///
+/// On RIOT these map to the `THREAD_CREATE_*` flags.
+///
+/// [`BuilderExt::flags`]: trait.BuilderExt.html#method.flags
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct CreateFlags(u8);
+
+impl CreateFlags {
+    /// The thread does not run until it is unparked.
+    ///
+    /// Other OS emulate it by parking the thread, which may wake up spuriously.
+    pub const SLEEPING: CreateFlags = CreateFlags(1 << 0);
+
+    /// Do not yield to the new thread, even if it is more urgent. Ignored by other OS.
+    pub const WOUT_YIELD: CreateFlags = CreateFlags(1 << 1);
+
+    /// Measure the stack usage and detect overflows, the same as
+    /// [`BuilderExt::stack_check`].
+    ///
+    /// [`BuilderExt::stack_check`]: trait.BuilderExt.html#method.stack_check
+    pub const STACKTEST: CreateFlags = CreateFlags(1 << 2);
+
+    const ALL: u8 = 0b111;
+
+    /// No flags set.
+    pub const fn empty() -> CreateFlags {
+        CreateFlags(0)
+    }
+
+    /// Converts the bits, returning `None` if any of them does not correspond to a flag.
+    pub fn from_bits(bits: u8) -> Option<CreateFlags> {
+        if bits & !CreateFlags::ALL == 0 {
+            Some(CreateFlags(bits))
+        } else {
+            None
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if all of the flags in `other` are set.
+    pub fn contains(self, other: CreateFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for CreateFlags {
+    type Output = CreateFlags;
+    fn bitor(self, other: CreateFlags) -> Self::Output {
+        CreateFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for CreateFlags {
+    fn bitor_assign(&mut self, other: CreateFlags) {
+        self.0 |= other.0
+    }
+}
+
+impl BitAnd for CreateFlags {
+    type Output = CreateFlags;
+    fn bitand(self, other: CreateFlags) -> Self::Output {
+        CreateFlags(self.0 & other.0)
+    }
+}
@@ -0,0 +1,49 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::io;
+use crate::sys;
+
+/// Scheduling state of a thread.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ThreadStatus {
+    /// Currently executing.
+    Running,
+    /// Ready to run, waiting for the scheduler.
+    Ready,
+    /// Blocked on a mutex, message, flag or I/O.
+    Blocked,
+    /// Sleeping until woken up.
+    Sleeping,
+    /// Stopped or exited, but not cleaned up yet.
+    Stopped,
+    /// The OS reported a state without equivalent.
+    Unknown,
+}
+
+/// Snapshot of a thread's runtime statistics, as returned by [list].
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    /// Equal to the [ThreadId] of the thread's handle, the pid on RIOT and the tid on Linux.
+    ///
+    /// [ThreadId]: super::ThreadId
+    pub id: sys::ThreadId,
+    /// On RIOT cut off after 32 bytes, and like the stack only known with `DEVELHELP`.
+    pub name: Option<String>,
+    /// Raw scheduling priority of the OS.
+    pub priority: i32,
+    pub status: ThreadStatus,
+    /// Size of the stack in bytes, if known.
+    pub stack_size: Option<usize>,
+    /// High-water mark of the stack usage in bytes, if known.
+    ///
+    /// On RIOT only known for threads created with `THREAD_CREATE_STACKTEST`.
+    pub stack_used: Option<usize>,
+}
+
+/// Returns every live thread of the process.
+///
+/// On RIOT the names and stacks require `DEVELHELP`. On Linux it reads `/proc/self/task`.
+pub fn list() -> Result<Vec<ThreadInfo>, io::Error> {
+    sys::list()
+}
@@ -0,0 +1,375 @@
+// Configure the module which shall be use
+// * RIOT
+use core::fmt;
+
+use crate::sys;
+/// A handle to a thread.
+///
+/// Every OS exposes the same methods: `id`, `name`, `unpark` and `set_flags`.
+pub use crate::sys::Thread;
+/// Identifies a running thread, it is the pid on RIOT and the tid on Linux.
+///
+/// Both reuse the id of an exited thread, so it is only unique among the running threads.
+pub use crate::sys::ThreadId;
+pub use crate::sys::LocalKey;
+use crate::time;
+
+mod create_flags;
+mod info;
+pub mod pool;
+mod priority;
+mod scope;
+mod stack;
+
+pub use self::create_flags::*;
+pub use self::info::*;
+pub use self::priority::*;
+pub use self::scope::*;
+pub use self::stack::*;
+
+/// An owned permission to join on a thread (block on its termination).
+///
+/// Dropping the handle detaches the thread. The thread keeps running and its stack stays alive
+/// until it exited. On RIOT the stack of a detached thread is released after the thread exited,
+/// by a later spawn, drop of a handle or exit of another thread spawned through os-glue. This
+/// also makes a caller-provided [`Stack`] available again.
+///
+/// [`Stack`]: struct.Stack.html
+pub struct JoinHandle(sys::JoinHandle<()>);
+pub struct Builder<T>(T);
+
+impl BuilderExt for Builder<sys::Builder> {
+    type JoinHandle = <sys::Builder as BuilderExt>::JoinHandle;
+
+    fn new() -> Self {
+        Builder(sys::Builder::new())
+    }
+
+    fn name(self, name: &'static str) -> Self {
+        Builder(<sys::Builder as BuilderExt>::name(self.0, name))
+    }
+
+    fn stack_size(self, stack_size: i32) -> Self {
+        Builder(<sys::Builder as BuilderExt>::stack_size(self.0, stack_size))
+    }
+
+    fn priority(self, priority: Priority) -> Self {
+        Builder(self.0.priority(priority))
+    }
+
+    fn flags(self, flags: CreateFlags) -> Self {
+        Builder(self.0.flags(flags))
+    }
+
+    fn stack_check(self, enabled: bool) -> Self {
+        Builder(self.0.stack_check(enabled))
+    }
+
+    fn stack(self, stack: Stack) -> Self {
+        Builder(self.0.stack(stack))
+    }
+
+    fn spawn<F>(self, f: F) -> Result<Self::JoinHandle, SpawnError>
+    where
+        F: FnOnce() -> (),
+        F: Send + 'static,
+    {
+        <sys::Builder as BuilderExt>::spawn(self.0, f)
+    }
+}
+
+impl JoinHandle {
+    pub fn thread(&self) -> &sys::Thread {
+        self.0.thread()
+    }
+
+    /// Returns the most stack the thread used so far, in bytes.
+    ///
+    /// Only available on RIOT for threads spawned with [`BuilderExt::stack_check`].
+    ///
+    /// [`BuilderExt::stack_check`]: trait.BuilderExt.html#method.stack_check
+    pub fn stack_high_water_mark(&self) -> Option<usize> {
+        self.0.stack_high_water_mark()
+    }
+
+    /// Checks whether the thread overflowed its stack by verifying the canary below it.
+    ///
+    /// Always succeeds for threads spawned without [`BuilderExt::stack_check`].
+    ///
+    /// [`BuilderExt::stack_check`]: trait.BuilderExt.html#method.stack_check
+    pub fn check_stack(&self) -> Result<(), StackOverflow> {
+        self.0.check_stack()
+    }
+
+    /// Detaches the thread, see [`JoinHandle`] for the semantics.
+    ///
+    /// A thread cannot release its own stack. So on RIOT the stacks of exited detached threads
+    /// remain allocated until the next spawn, drop or exit, usually only the stack of the last
+    /// exited thread. At most one stack per thread slot (`MAXTHREADS`) is outstanding.
+    ///
+    /// # Examples
+    /// ```
+    /// use os_glue::thread;
+    /// use os_glue::time::Duration;
+    ///
+    /// let handle = thread::spawn(|| thread::sleep(Duration::from_millis(10)));
+    /// handle.detach();
+    ///
+    /// // The stack of the detached thread is not released while it is running.
+    /// thread::spawn(|| {}).join();
+    /// ```
+    ///
+    /// [`JoinHandle`]: struct.JoinHandle.html
+    pub fn detach(self) {
+        self.0.detach()
+    }
+
+    /// Waits for the associated thread to finish.
+    #[cfg(target_os = "riot")]
+    pub fn join(self) {
+        self.0.join()
+    }
+
+    /// Waits for the associated thread to finish and hands back the stack given to
+    /// [`BuilderExt::stack`], if any.
+    ///
+    /// # Panics
+    /// Panics if the associated thread panicked.
+    ///
+    /// [`BuilderExt::stack`]: trait.BuilderExt.html#method.stack
+    pub fn join_stack(self) -> Option<Stack> {
+        self.0.join_stack()
+    }
+
+    /// Waits for the associated thread to finish.
+    ///
+    /// # Panics
+    /// Panics if the associated thread panicked.
+    #[cfg(feature = "std")]
+    pub fn join(self) {
+        self.join_inner().unwrap()
+    }
+
+    /// Waits for the associated thread to finish, returning its panic instead of propagating it.
+    pub(crate) fn join_inner(self) -> Result<(), sys::Panic> {
+        self.0.join_inner()
+    }
+}
+
+impl From<sys::JoinHandle<()>> for JoinHandle {
+    fn from(handle: sys::JoinHandle<()>) -> Self {
+        JoinHandle(handle)
+    }
+}
+
+/// Spawns a new thread, returning a [`JoinHandle`] for it.
+///
+/// [`JoinHandle`]: struct.JoinHandle.html
+pub fn spawn<F>(f: F) -> JoinHandle
+where
+    F: FnOnce() -> (),
+    F: Send + 'static,
+{
+    sys::spawn::<_, Builder<_>>(f)
+}
+
+/// Gets a handle to the thread that invokes it.
+pub fn current() -> sys::Thread {
+    sys::current()
+}
+
+/// Determines whether the current thread is unwinding because of panic.
+pub fn panicking() -> bool {
+    sys::panicking()
+}
+
+/// Blocks unless or until the current thread's token is made available.
+pub fn park() {
+    sys::park()
+}
+
+/// Blocks unless or until the current thread's token is made available or
+/// the specified duration has been reached (may wake spuriously).
+pub fn park_timeout(duration: time::Duration) {
+    sys::park_timeout(duration)
+}
+
+/// Puts the current thread to sleep for the specified amount of time.
+pub fn sleep(duration: time::Duration) {
+    sys::sleep(duration)
+}
+
+/// Cooperatively gives up a timeslice to the OS scheduler.
+pub fn yield_now() {
+    sys::yield_now()
+}
+
+/// Bit mask of thread flags, set on a thread with [`Thread::set_flags`].
+///
+/// On RIOT these are the kernel's thread flags and setting them is safe from interrupt context.
+/// Other OS emulate them. The bits from `1 << 10` upwards are reserved for internal use by RIOT
+/// and os-glue.
+///
+/// [`Thread::set_flags`]: struct.Thread.html#method.set_flags
+pub type Flags = u16;
+
+/// Blocks until any of the flags in `mask` is set on the current thread or the timeout elapsed.
+///
+/// Returns the received flags, which are cleared, or `None` on timeout.
+pub fn wait_flags_any(mask: Flags, timeout: Option<time::Duration>) -> Option<Flags> {
+    sys::wait_flags_any(mask, timeout)
+}
+
+/// Blocks until all of the flags in `mask` are set on the current thread or the timeout elapsed.
+///
+/// Returns the received flags, which are cleared, or `None` on timeout. On timeout no flags are
+/// consumed.
+pub fn wait_flags_all(mask: Flags, timeout: Option<time::Duration>) -> Option<Flags> {
+    sys::wait_flags_all(mask, timeout)
+}
+
+/// Clears the flags in `mask` on the current thread, returning those which were set.
+pub fn clear_flags(mask: Flags) -> Flags {
+    sys::clear_flags(mask)
+}
+
+/// Returns the remaining stack of the current thread in bytes, if the OS can tell.
+pub fn stack_remaining() -> Option<usize> {
+    sys::stack_remaining()
+}
+
+/// The reasons why spawning a thread can fail.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SpawnError {
+    /// The priority is out of range.
+    InvalidPriority,
+    /// The process lacks the permission for the requested priority.
+    PermissionDenied,
+    /// The maximum number of threads is reached.
+    TooManyThreads,
+    /// The stack is below the minimum size of the OS, after subtracting what os-glue places in
+    /// front of it.
+    StackTooSmall,
+    /// The stack could not be allocated.
+    OutOfMemory,
+    /// Any other error reported by the OS, as its raw error code.
+    Os(i32),
+    /// Spawning failed without an error code, e.g. the thread exited before it could report
+    /// whether its priority was applied.
+    Unknown,
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpawnError::InvalidPriority => write!(fmt, "priority is out of range"),
+            SpawnError::PermissionDenied => write!(fmt, "no permission for the priority"),
+            SpawnError::TooManyThreads => write!(fmt, "too many threads"),
+            SpawnError::StackTooSmall => write!(fmt, "stack is too small"),
+            SpawnError::OutOfMemory => write!(fmt, "out of memory for the stack"),
+            SpawnError::Os(code) => write!(fmt, "os error {}", code),
+            SpawnError::Unknown => write!(fmt, "unknown error"),
+        }
+    }
+}
+
+/// The canary below a thread's stack was overwritten.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct StackOverflow;
+
+impl fmt::Display for StackOverflow {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "thread overflowed its stack")
+    }
+}
+
+pub trait BuilderExt
+where
+    Self: Sized,
+{
+    type JoinHandle: Send + 'static;
+
+    fn new() -> Self;
+
+    /// Names the thread-to-be.
+    ///
+    /// RIOT requires a NUL-terminated name. Names without terminator are copied next to the
+    /// thread's stack.
+    fn name(self, name: &'static str) -> Self;
+
+    fn stack_size(self, _stack_size: i32) -> Self {
+        self
+    }
+
+    /// Sets the scheduling priority of the thread-to-be.
+    ///
+    /// Spawning fails if the OS refuses the priority, e.g. because of missing permissions.
+    fn priority(self, _priority: Priority) -> Self {
+        self
+    }
+
+    /// Sets the options for creating the thread-to-be.
+    fn flags(self, _flags: CreateFlags) -> Self {
+        self
+    }
+
+    /// Enables stack usage measurement and overflow detection.
+    ///
+    /// On RIOT the thread is created with `THREAD_CREATE_STACKTEST` and a canary is placed below
+    /// its stack. Other OS already protect their stacks with guard pages.
+    fn stack_check(self, _enabled: bool) -> Self {
+        self
+    }
+
+    /// Spawns the thread onto the provided stack instead of allocating one.
+    ///
+    /// On RIOT the name, the closure and the join state are placed in the stack as well, so
+    /// spawning does not allocate at all and the whole stack determines the stack size. Other OS
+    /// allocate their stacks themselves and leave it unused. Either way
+    /// [`JoinHandle::join_stack`] hands it back, see [`StaticStack`] for an example. By default the
+    /// stack is dropped, which makes it available again right away.
+    ///
+    /// [`JoinHandle::join_stack`]: struct.JoinHandle.html#method.join_stack
+    /// [`StaticStack`]: struct.StaticStack.html
+    fn stack(self, _stack: Stack) -> Self {
+        self
+    }
+
+    fn spawn<F>(self, f: F) -> Result<Self::JoinHandle, SpawnError>
+    where
+        F: FnOnce() -> (),
+        F: Send + 'static;
+}
+
+/// Declare a new thread local storage key of type [`LocalKey`].
+///
+/// On RIOT the values are kept per thread and dropped once a thread spawned through os-glue
+/// exits. Other OS use the thread locals of the standard library.
+///
+/// [`LocalKey`]: thread/struct.LocalKey.html
+#[cfg(target_os = "riot")]
+#[macro_export]
+macro_rules! thread_local {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr; $($rest:tt)*) => {
+        $crate::__thread_local_inner!($(#[$attr])* $vis $name, $t, $init);
+        $crate::thread_local!($($rest)*);
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty = $init:expr) => {
+        $crate::__thread_local_inner!($(#[$attr])* $vis $name, $t, $init);
+    };
+}
+
+#[cfg(target_os = "riot")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __thread_local_inner {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $t:ty, $init:expr) => {
+        $(#[$attr])* $vis static $name: $crate::thread::LocalKey<$t> = {
+            fn __init() -> $t {
+                $init
+            }
+            $crate::thread::LocalKey::new(__init)
+        };
+    };
+}
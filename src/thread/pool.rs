@@ -0,0 +1,197 @@
+//! A fixed pool of worker threads executing jobs from a bounded queue.
+//!
+//! # Examples
+//! ```
+//! use os_glue::thread::pool::ThreadPool;
+//!
+//! let pool = ThreadPool::new(2, 8).unwrap();
+//!
+//! for i in 0..4 {
+//!     pool.execute(move || println!("job {}", i)).unwrap();
+//! }
+//!
+//! // Runs the queued jobs to completion.
+//! pool.join();
+//! ```
+
+use alloc::boxed::{Box, FnBox};
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{current, wait_flags_any, Builder, BuilderExt, Flags, JoinHandle, SpawnError, Thread};
+use crate::sync::Mutex;
+use crate::sys;
+
+/// Thread flag used to wake up idle workers and blocked submitters.
+const POOL_FLAG: Flags = 1 << 11;
+
+type Job = Box<dyn FnBox() + Send>;
+
+/// A fixed number of worker threads sharing a bounded job queue.
+///
+/// Dropping the pool shuts it down and joins the workers, see [join]. A panicking job does not
+/// affect its worker, which continues with the queue. On RIOT a panic aborts anyway.
+///
+/// [join]: ThreadPool::join
+pub struct ThreadPool {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle>,
+}
+
+/// The pool was shut down and accepts no more jobs.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ShutDown;
+
+struct Shared {
+    state: Mutex<State>,
+    capacity: usize,
+}
+
+struct State {
+    jobs: VecDeque<Job>,
+    shutdown: bool,
+    idle_workers: Vec<Thread>,
+    blocked_submitters: Vec<Thread>,
+}
+
+impl ThreadPool {
+    /// Spawns `workers` threads with the default configuration and a queue holding up to
+    /// `capacity` jobs.
+    pub fn new(workers: usize, capacity: usize) -> Result<ThreadPool, SpawnError> {
+        ThreadPool::with_builder(workers, capacity, Builder::<sys::Builder>::new)
+    }
+
+    /// Spawns `workers` threads, each configured by a builder from `builder`, e.g. to set the
+    /// stack size or priority.
+    pub fn with_builder<B, F>(
+        workers: usize,
+        capacity: usize,
+        mut builder: F,
+    ) -> Result<ThreadPool, SpawnError>
+    where
+        B: BuilderExt<JoinHandle = JoinHandle>,
+        F: FnMut() -> B,
+    {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                jobs: VecDeque::with_capacity(capacity),
+                shutdown: false,
+                idle_workers: Vec::with_capacity(workers),
+                blocked_submitters: Vec::new(),
+            }),
+            capacity: capacity.max(1),
+        });
+
+        let mut pool = ThreadPool {
+            shared,
+            workers: Vec::with_capacity(workers),
+        };
+
+        for _ in 0..workers {
+            let shared = pool.shared.clone();
+            // On failure the pool is dropped, which joins the workers spawned so far.
+            let worker = builder().spawn(move || shared.work())?;
+            pool.workers.push(worker);
+        }
+
+        Ok(pool)
+    }
+
+    /// Queues a job, blocking while the queue is full.
+    pub fn execute<F>(&self, f: F) -> Result<(), ShutDown>
+    where
+        F: FnOnce() -> (),
+        F: Send + 'static,
+    {
+        let mut job = Some(Box::new(f) as Job);
+
+        loop {
+            {
+                let mut state = self.shared.state.lock();
+                if state.shutdown {
+                    return Err(ShutDown);
+                }
+
+                if state.jobs.len() < self.shared.capacity {
+                    state.jobs.push_back(job.take().unwrap());
+                    if let Some(worker) = state.idle_workers.pop() {
+                        worker.set_flags(POOL_FLAG);
+                    }
+                    return Ok(());
+                }
+
+                state.blocked_submitters.push(current());
+            }
+
+            wait_flags_any(POOL_FLAG, None);
+        }
+    }
+
+    /// Stops accepting jobs. Queued jobs are still executed.
+    pub fn shutdown(&self) {
+        let mut state = self.shared.state.lock();
+        let state = &mut *state;
+        state.shutdown = true;
+
+        for thread in state
+            .idle_workers
+            .drain(..)
+            .chain(state.blocked_submitters.drain(..))
+        {
+            thread.set_flags(POOL_FLAG);
+        }
+    }
+
+    /// Shuts the pool down and waits until the workers executed every queued job and exited.
+    pub fn join(self) {
+        // Done on drop.
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.shutdown();
+        for worker in self.workers.drain(..) {
+            // Workers catch the panics of their jobs, propagating one here would abort while
+            // unwinding anyway.
+            let _ = worker.join_inner();
+        }
+    }
+}
+
+impl Shared {
+    fn work(&self) {
+        loop {
+            {
+                let mut state = self.state.lock();
+
+                if let Some(job) = state.jobs.pop_front() {
+                    if let Some(submitter) = state.blocked_submitters.pop() {
+                        submitter.set_flags(POOL_FLAG);
+                    }
+                    drop(state);
+
+                    // The panic hook already reported a panic.
+                    let _ = sys::catch_unwind(move || job());
+                    continue;
+                }
+
+                if state.shutdown {
+                    return;
+                }
+
+                state.idle_workers.push(current());
+            }
+
+            wait_flags_any(POOL_FLAG, None);
+        }
+    }
+}
+
+impl fmt::Display for ShutDown {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "thread pool is shut down")
+    }
+}
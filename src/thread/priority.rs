@@ -0,0 +1,85 @@
+use super::SpawnError;
+use crate::sys;
+
+/// Scheduling priority of a thread.
+///
+/// Follows RIOT's convention, lower values are more urgent. The range is
+/// `0..PRIORITY_LEVELS`, which is `SCHED_PRIO_LEVELS` on RIOT and 16 otherwise. On Linux the
+/// levels are mapped onto nice values, [MAIN] being the default nice value of 0. Increasing
+/// the urgency beyond [MAIN] requires `CAP_SYS_NICE` there.
+///
+/// [MAIN]: Priority::MAIN
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Priority(u8);
+
+/// Number of priority levels.
+pub const PRIORITY_LEVELS: u8 = sys::PRIORITY_LEVELS;
+
+impl Priority {
+    /// The most urgent priority.
+    pub const HIGHEST: Priority = Priority(0);
+
+    /// Halfway between [HIGHEST] and [MAIN].
+    ///
+    /// [HIGHEST]: Priority::HIGHEST
+    /// [MAIN]: Priority::MAIN
+    pub const HIGH: Priority = Priority(sys::PRIORITY_MAIN / 2);
+
+    /// The priority of the main thread.
+    pub const MAIN: Priority = Priority(sys::PRIORITY_MAIN);
+
+    /// Halfway between [MAIN] and [LOWEST].
+    ///
+    /// [MAIN]: Priority::MAIN
+    /// [LOWEST]: Priority::LOWEST
+    pub const LOW: Priority = Priority((sys::PRIORITY_MAIN + PRIORITY_LEVELS - 1) / 2);
+
+    /// The least urgent priority, shared with the idle thread on RIOT.
+    pub const LOWEST: Priority = Priority(PRIORITY_LEVELS - 1);
+
+    /// Creates a priority from a raw level, see [Priority] for its meaning.
+    ///
+    /// Fails with [SpawnError::InvalidPriority] if the level is out of range.
+    pub fn new(level: u8) -> Result<Priority, SpawnError> {
+        if level < PRIORITY_LEVELS {
+            Ok(Priority(level))
+        } else {
+            Err(SpawnError::InvalidPriority)
+        }
+    }
+
+    /// The most urgent priority.
+    pub const fn highest() -> Priority {
+        Priority::HIGHEST
+    }
+
+    /// The least urgent priority.
+    pub const fn lowest() -> Priority {
+        Priority::LOWEST
+    }
+
+    /// Returns a priority `levels` more urgent, saturating at [HIGHEST].
+    ///
+    /// [HIGHEST]: Priority::HIGHEST
+    pub fn higher(self, levels: u8) -> Priority {
+        Priority(self.0.saturating_sub(levels))
+    }
+
+    /// Returns a priority `levels` less urgent, saturating at [LOWEST].
+    ///
+    /// [LOWEST]: Priority::LOWEST
+    pub fn lower(self, levels: u8) -> Priority {
+        Priority(self.0.saturating_add(levels).min(Priority::LOWEST.0))
+    }
+
+    /// Returns the raw level, see [Priority] for its meaning.
+    pub fn as_raw(self) -> u8 {
+        self.0
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::MAIN
+    }
+}
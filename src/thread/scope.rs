@@ -0,0 +1,130 @@
+use alloc::boxed::{Box, FnBox};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::marker::PhantomData;
+use core::mem;
+
+use super::{Builder, BuilderExt, JoinHandle, SpawnError};
+use crate::sys;
+
+/// Creates a scope for spawning threads which may borrow from the calling stack.
+///
+/// Every thread spawned within the scope, which is not joined manually, is joined before this
+/// function returns.
+///
+/// # Examples
+/// ```
+/// use os_glue::thread;
+///
+/// let mut numbers = [1, 2, 3];
+///
+/// thread::scope(|s| {
+///     for number in numbers.iter_mut() {
+///         s.spawn(move || *number *= 2).unwrap();
+///     }
+/// });
+///
+/// assert_eq!(numbers, [2, 4, 6]);
+/// ```
+pub fn scope<'env, F, R>(f: F) -> R
+where
+    F: FnOnce(&Scope<'env>) -> R,
+{
+    let scope = Scope {
+        handles: RefCell::new(Vec::new()),
+        _marker: PhantomData,
+    };
+
+    // Joins even if `f` unwinds.
+    let guard = JoinAll(&scope);
+    let result = f(&scope);
+    drop(guard);
+
+    result
+}
+
+/// A scope for spawning threads, created by [scope].
+pub struct Scope<'env> {
+    handles: RefCell<Vec<Option<JoinHandle>>>,
+    // Invariant over 'env
+    _marker: PhantomData<&'env mut &'env ()>,
+}
+
+/// An owned permission to join on a scoped thread.
+pub struct ScopedJoinHandle<'scope, 'env: 'scope> {
+    scope: &'scope Scope<'env>,
+    index: usize,
+}
+
+struct JoinAll<'scope, 'env: 'scope>(&'scope Scope<'env>);
+
+impl<'env> Scope<'env> {
+    /// Spawns a scoped thread with the default configuration.
+    pub fn spawn<'scope, F>(
+        &'scope self,
+        f: F,
+    ) -> Result<ScopedJoinHandle<'scope, 'env>, SpawnError>
+    where
+        F: FnOnce() -> (),
+        F: Send + 'env,
+    {
+        self.spawn_with(Builder::<sys::Builder>::new(), f)
+    }
+
+    /// Spawns a scoped thread configured by `builder`.
+    pub fn spawn_with<'scope, B, F>(
+        &'scope self,
+        builder: B,
+        f: F,
+    ) -> Result<ScopedJoinHandle<'scope, 'env>, SpawnError>
+    where
+        B: BuilderExt<JoinHandle = JoinHandle>,
+        F: FnOnce() -> (),
+        F: Send + 'env,
+    {
+        let main: Box<dyn FnBox() + Send + 'env> = Box::new(f);
+        // The scope joins the thread before 'env ends.
+        let main: Box<dyn FnBox() + Send + 'static> = unsafe { mem::transmute(main) };
+
+        let handle = builder.spawn(move || main())?;
+
+        let mut handles = self.handles.borrow_mut();
+        handles.push(Some(handle));
+
+        Ok(ScopedJoinHandle {
+            scope: self,
+            index: handles.len() - 1,
+        })
+    }
+}
+
+impl<'scope, 'env> ScopedJoinHandle<'scope, 'env> {
+    /// Waits for the associated thread to finish.
+    pub fn join(self) {
+        let handle = self.scope.handles.borrow_mut()[self.index].take();
+        if let Some(handle) = handle {
+            handle.join()
+        }
+    }
+}
+
+impl<'scope, 'env> Drop for JoinAll<'scope, 'env> {
+    fn drop(&mut self) {
+        let handles = mem::replace(&mut *self.0.handles.borrow_mut(), Vec::new());
+
+        // Every thread borrows from the stack, so all are joined before a panic is propagated.
+        let mut panic = None;
+        for handle in handles.into_iter().filter_map(|handle| handle) {
+            if let Err(payload) = handle.join_inner() {
+                panic.get_or_insert(payload);
+            }
+        }
+
+        if let Some(payload) = panic {
+            // Panicking again while unwinding aborts, the first panic wins.
+            if !sys::panicking() {
+                sys::resume_unwind(payload)
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use core::cell::UnsafeCell;
+use core::mem;
+use core::slice;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Memory for the stack of one thread at a time, placed in a `static` without `static mut`.
+///
+/// `T` is a byte array implementing [StackMemory], its size determines the stack size.
+///
+/// # Examples
+/// ```no_run
+/// use os_glue::thread::{self, BuilderExt, StaticStack};
+///
+/// static STACK: StaticStack<[u8; 1024]> = StaticStack::new([0; 1024]);
+///
+/// let handle = thread::Builder::new()
+///     .stack(STACK.take().unwrap())
+///     .spawn(|| {})
+///     .unwrap();
+///
+/// // The stack is handed back once the thread exited and may be used for the next thread.
+/// let stack = handle.join_stack();
+/// ```
+pub struct StaticStack<T> {
+    taken: AtomicBool,
+    memory: UnsafeCell<T>,
+}
+
+// The memory is only reachable through the one `Stack` handed out at a time.
+unsafe impl<T: StackMemory> Sync for StaticStack<T> {}
+
+impl<T: StackMemory> StaticStack<T> {
+    pub const fn new(memory: T) -> StaticStack<T> {
+        StaticStack {
+            taken: AtomicBool::new(false),
+            memory: UnsafeCell::new(memory),
+        }
+    }
+
+    /// Takes the stack, returns `None` while a [Stack] of it exists.
+    pub fn take(&'static self) -> Option<Stack> {
+        if self.taken.swap(true, Ordering::Acquire) {
+            return None;
+        }
+
+        let memory = self.memory.get() as *mut u8;
+        Some(Stack {
+            memory: unsafe { slice::from_raw_parts_mut(memory, mem::size_of::<T>()) },
+            taken: &self.taken,
+        })
+    }
+}
+
+/// Exclusive access to a [StaticStack], which can be taken again once this is dropped.
+///
+/// Given to [`BuilderExt::stack`] and handed back by [`JoinHandle::join_stack`].
+///
+/// [`BuilderExt::stack`]: trait.BuilderExt.html#method.stack
+/// [`JoinHandle::join_stack`]: struct.JoinHandle.html#method.join_stack
+pub struct Stack {
+    memory: &'static mut [u8],
+    taken: &'static AtomicBool,
+}
+
+impl Stack {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.memory.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.memory.is_empty()
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn as_ptr(&self) -> *const u8 {
+        self.memory.as_ptr()
+    }
+
+    #[allow(dead_code)]
+    #[inline]
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.memory.as_mut_ptr()
+    }
+}
+
+impl Drop for Stack {
+    fn drop(&mut self) {
+        self.taken.store(false, Ordering::Release);
+    }
+}
+
+/// Byte arrays which can back a [StaticStack].
+///
+/// Implemented for `[u8; N]` with `N` a multiple of 256 up to 8192 and the powers of two up to
+/// 65536. It is sealed, since the memory is handed out as bytes and must hold nothing else.
+pub trait StackMemory: private::Sealed {}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! stack_memory {
+    ($($len:expr),*) => {
+        $(
+            impl private::Sealed for [u8; $len] {}
+            impl StackMemory for [u8; $len] {}
+        )*
+    };
+}
+
+stack_memory!(
+    256, 512, 768, 1024, 1280, 1536, 1792, 2048, 2304, 2560, 2816, 3072, 3328, 3584, 3840, 4096,
+    4352, 4608, 4864, 5120, 5376, 5632, 5888, 6144, 6400, 6656, 6912, 7168, 7424, 7680, 7936, 8192,
+    16384, 32768, 65536
+);
@@ -1,6 +1,10 @@
 pub use core::time::Duration;
 
+use core::fmt;
+use core::future::Future;
 use core::ops::{Add, Sub};
+use core::pin::Pin;
+use core::task::{LocalWaker, Poll};
 use crate::sys;
 
 /// A measurment of a monotonically nondecreasing clock. Opaque and useful only with [Duration].
@@ -64,3 +68,81 @@ impl Add<Duration> for Instant {
         Instant(self.0 + other)
     }
 }
+
+/// Returns a future which completes `duration` after its creation.
+///
+/// On RIOT the waker is woken from interrupt context, which the wakers of [`task::Executor`]
+/// support.
+///
+/// [`task::Executor`]: ../task/struct.Executor.html
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep(sys::Sleep::new(duration))
+}
+
+/// Requires `future` to complete within `duration`.
+///
+/// # Examples
+/// ```
+/// use os_glue::task;
+/// use os_glue::time::{self, Duration};
+///
+/// let never = time::sleep(Duration::from_secs(60));
+/// let result = task::block_on(time::timeout(Duration::from_millis(10), never));
+/// assert!(result.is_err());
+/// ```
+pub fn timeout<F>(duration: Duration, future: F) -> Timeout<F>
+where
+    F: Future,
+{
+    Timeout {
+        future,
+        sleep: sleep(duration),
+    }
+}
+
+/// Future returned by [sleep].
+pub struct Sleep(sys::Sleep);
+
+/// Future returned by [timeout].
+pub struct Timeout<F> {
+    future: F,
+    sleep: Sleep,
+}
+
+/// The future of a [timeout] did not complete in time.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Elapsed;
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+        unsafe { self.map_unchecked_mut(|sleep| &mut sleep.0) }.poll(lw)
+    }
+}
+
+impl<F> Future for Timeout<F>
+where
+    F: Future,
+{
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        // Neither field is moved out of the pinned timeout.
+        let this = unsafe { Pin::get_mut_unchecked(self) };
+
+        let future = unsafe { Pin::new_unchecked(&mut this.future) };
+        if let Poll::Ready(output) = future.poll(lw) {
+            return Poll::Ready(Ok(output));
+        }
+
+        let sleep = unsafe { Pin::new_unchecked(&mut this.sleep) };
+        sleep.poll(lw).map(|()| Err(Elapsed))
+    }
+}
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "deadline has elapsed")
+    }
+}
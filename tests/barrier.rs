@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use os_glue::sync::Barrier;
+use os_glue::thread;
+
+const THREADS: usize = 4;
+
+#[test]
+fn barrier_elects_a_single_leader_per_generation() {
+    let barrier = Arc::new(Barrier::new(THREADS));
+    let leaders = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let barrier = barrier.clone();
+            let leaders = leaders.clone();
+            thread::spawn(move || {
+                for _ in 0..3 {
+                    if barrier.wait().is_leader() {
+                        leaders.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join();
+    }
+
+    assert_eq!(leaders.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn barrier_releases_no_thread_before_all_arrived() {
+    let barrier = Arc::new(Barrier::new(THREADS));
+    let arrived = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let barrier = barrier.clone();
+            let arrived = arrived.clone();
+            thread::spawn(move || {
+                arrived.fetch_add(1, Ordering::SeqCst);
+                barrier.wait();
+                assert_eq!(arrived.load(Ordering::SeqCst), THREADS);
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join();
+    }
+}
+
+#[test]
+fn barrier_ignores_foreign_wakeups() {
+    let barrier = Arc::new(Barrier::new(2));
+    let passed = Arc::new(AtomicBool::new(false));
+
+    let handle = {
+        let barrier = barrier.clone();
+        let passed = passed.clone();
+        thread::spawn(move || {
+            barrier.wait();
+            passed.store(true, Ordering::SeqCst);
+        })
+    };
+
+    // Another user of the barrier's flag wakes the waiting thread before the barrier is full.
+    thread::sleep(Duration::from_millis(50));
+    handle.thread().set_flags(1 << 13);
+    thread::sleep(Duration::from_millis(50));
+    assert!(!passed.load(Ordering::SeqCst));
+
+    barrier.wait();
+    handle.join();
+    assert!(passed.load(Ordering::SeqCst));
+}
@@ -0,0 +1,64 @@
+//! Dropping or detaching a running thread's handle must keep its stack alive until it exited.
+//!
+//! Only the facade is covered here, on std the standard library owns the stacks. RIOT's
+//! `reap_detached` needs a RIOT board, which libtest does not run on.
+#![cfg(feature = "std")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use os_glue::thread;
+use os_glue::time::Duration;
+
+const THREADS: usize = 4;
+
+/// Uses its stack after the handle was dropped, which crashes if the stack was released.
+fn busy(finished: &'static AtomicUsize) {
+    let mut scratch = [0u8; 128];
+    thread::sleep(Duration::from_millis(20));
+    for (i, byte) in scratch.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    assert!(scratch.iter().enumerate().all(|(i, &byte)| byte == i as u8));
+    finished.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Fails instead of hanging if a thread stopped with its handle.
+fn wait_for(finished: &AtomicUsize, count: usize) {
+    let start = Instant::now();
+    while finished.load(Ordering::SeqCst) < count {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "a detached thread never finished"
+        );
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn spawn_and_drop_keeps_the_stack_alive() {
+    static FINISHED: AtomicUsize = AtomicUsize::new(0);
+
+    for _ in 0..THREADS {
+        drop(thread::spawn(|| busy(&FINISHED)));
+    }
+
+    // Spawning reclaims the stacks of exited threads only, not of running ones.
+    thread::spawn(|| {}).join();
+
+    wait_for(&FINISHED, THREADS);
+}
+
+#[test]
+fn detached_threads_release_their_stacks_for_new_threads() {
+    static FINISHED: AtomicUsize = AtomicUsize::new(0);
+
+    for round in 1..=3 {
+        for _ in 0..THREADS {
+            thread::spawn(|| busy(&FINISHED)).detach();
+        }
+        wait_for(&FINISHED, round * THREADS);
+    }
+
+    thread::spawn(|| {}).join();
+}
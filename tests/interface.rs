@@ -0,0 +1,64 @@
+#![cfg(feature = "std")]
+
+use std::io::{self, ErrorKind};
+use std::net::{Ipv6Addr, UdpSocket};
+
+use os_glue::net::{self, Interface, Ipv6AddrFlags, Ipv6Scope};
+
+fn loopback() -> Interface {
+    net::interfaces()
+        .unwrap()
+        .into_iter()
+        .find(|interface| interface.name().unwrap() == "lo")
+        .expect("no loopback interface")
+}
+
+#[test]
+fn interfaces_are_listed_once_by_index() {
+    let interfaces = net::interfaces().unwrap();
+    assert!(!interfaces.is_empty());
+
+    for pair in interfaces.windows(2) {
+        assert!(pair[0].index() < pair[1].index());
+    }
+    for interface in &interfaces {
+        assert!(!interface.name().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn loopback_is_up_with_an_mtu() {
+    let loopback = loopback();
+    assert!(loopback.is_up().unwrap());
+    assert!(loopback.mtu().unwrap() >= 1280);
+}
+
+fn assert_not_available<T>(result: io::Result<T>) {
+    match result {
+        Ok(_) => panic!("loopback has an address"),
+        Err(error) => assert_eq!(error.kind(), ErrorKind::AddrNotAvailable),
+    }
+}
+
+#[test]
+fn loopback_has_no_eui() {
+    let loopback = loopback();
+    assert_not_available(net::eui48(&loopback));
+    assert_not_available(net::eui64(&loopback));
+}
+
+#[test]
+fn loopback_lists_its_ipv6_address() {
+    // IPv6 may be disabled, then the loopback has no address to list.
+    if UdpSocket::bind((Ipv6Addr::LOCALHOST, 0)).is_err() {
+        return;
+    }
+
+    let addrs = loopback().ipv6_addrs().unwrap();
+    let addr = addrs
+        .iter()
+        .find(|addr| addr.addr == Ipv6Addr::LOCALHOST)
+        .expect("::1 is not listed");
+    assert_eq!(addr.scope, Ipv6Scope::Host);
+    assert_eq!(addr.flags, Ipv6AddrFlags::empty());
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "std")]
+
+use std::io::{self, ErrorKind};
+use std::thread;
+
+use os_glue::net::{self, Interface, Ipv6AddrFlags, Ipv6Address, Ipv6Scope};
+
+fn loopback() -> Interface {
+    net::interfaces()
+        .unwrap()
+        .into_iter()
+        .find(|interface| interface.name().unwrap() == "lo")
+        .expect("no loopback interface")
+}
+
+fn is_listed(interface: &Interface, addr: Ipv6Address) -> bool {
+    interface
+        .ipv6_addrs()
+        .unwrap()
+        .iter()
+        .any(|listed| listed.addr == addr && listed.scope == Ipv6Scope::Global)
+}
+
+// Requires CAP_SYS_ADMIN to create a network namespace, run as root with `cargo test -- --ignored`.
+// A user namespace would lift that, but cannot be entered by a multithreaded process like the
+// test harness.
+#[test]
+#[ignore]
+fn ipv6_addr_add_list_remove_round_trip() {
+    // A network namespace belongs to the thread which created it, the host is left untouched.
+    let namespaced = thread::spawn(|| {
+        if unsafe { libc::unshare(libc::CLONE_NEWNET) } != 0 {
+            panic!("unshare failed: {}", io::Error::last_os_error());
+        }
+
+        let loopback = loopback();
+        let addr = Ipv6Address::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+
+        loopback
+            .add_ipv6_addr(addr, 64, Ipv6AddrFlags::empty())
+            .unwrap();
+        assert!(is_listed(&loopback, addr));
+
+        loopback.remove_ipv6_addr(addr).unwrap();
+        assert!(!is_listed(&loopback, addr));
+        assert!(loopback.remove_ipv6_addr(addr).is_err());
+    });
+
+    namespaced.join().unwrap();
+}
+
+#[test]
+fn adding_a_deprecated_ipv6_addr_is_rejected() {
+    let addr = Ipv6Address::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 2);
+    let error = loopback()
+        .add_ipv6_addr(addr, 64, Ipv6AddrFlags::DEPRECATED)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
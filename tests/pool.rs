@@ -0,0 +1,67 @@
+#![cfg(feature = "std")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use os_glue::thread::pool::{ShutDown, ThreadPool};
+
+#[test]
+fn pool_executes_every_queued_job_before_join_returns() {
+    let pool = ThreadPool::new(3, 2).unwrap();
+    let done = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..16 {
+        let done = done.clone();
+        pool.execute(move || {
+            done.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    }
+
+    pool.join();
+    assert_eq!(done.load(Ordering::SeqCst), 16);
+}
+
+#[test]
+fn pool_rejects_jobs_after_shutdown() {
+    let pool = ThreadPool::new(1, 1).unwrap();
+    pool.shutdown();
+    assert_eq!(pool.execute(|| {}), Err(ShutDown));
+}
+
+#[test]
+fn pool_survives_a_panicking_job() {
+    let pool = ThreadPool::new(2, 4).unwrap();
+    let done = Arc::new(AtomicUsize::new(0));
+
+    pool.execute(|| panic!("job failed")).unwrap();
+    for _ in 0..4 {
+        let done = done.clone();
+        pool.execute(move || {
+            done.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    }
+
+    // Dropping the pool must not propagate the panic of the first job.
+    drop(pool);
+    assert_eq!(done.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn pool_runs_later_jobs_on_the_worker_of_a_panicking_job() {
+    let pool = ThreadPool::new(1, 4).unwrap();
+    let done = Arc::new(AtomicUsize::new(0));
+
+    pool.execute(|| panic!("job failed")).unwrap();
+    for _ in 0..4 {
+        let done = done.clone();
+        pool.execute(move || {
+            done.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    }
+
+    pool.join();
+    assert_eq!(done.load(Ordering::SeqCst), 4);
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "std")]
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use os_glue::thread;
+use os_glue::time::Duration;
+
+#[test]
+fn scope_borrows_from_the_stack() {
+    let mut numbers = [1, 2, 3];
+
+    thread::scope(|s| {
+        for number in numbers.iter_mut() {
+            s.spawn(move || *number *= 2).unwrap();
+        }
+    });
+
+    assert_eq!(numbers, [2, 4, 6]);
+}
+
+#[test]
+fn scope_joins_every_thread_before_propagating_a_panic() {
+    let finished = AtomicUsize::new(0);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        thread::scope(|s| {
+            s.spawn(|| panic!("first")).unwrap();
+
+            for _ in 0..3 {
+                s.spawn(|| {
+                    thread::sleep(Duration::from_millis(20));
+                    finished.fetch_add(1, Ordering::SeqCst);
+                })
+                .unwrap();
+            }
+        })
+    }));
+
+    let payload = result.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"first"));
+    assert_eq!(finished.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn scope_propagates_the_first_panic_only() {
+    let result = panic::catch_unwind(|| {
+        thread::scope(|s| {
+            s.spawn(|| panic!("first")).unwrap();
+            s.spawn(|| panic!("second")).unwrap();
+        })
+    });
+
+    let payload = result.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"first"));
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "std")]
+
+use os_glue::net::{RecvFlags, UdpSocket, IPV6_LOOPBACK};
+
+fn pair() -> (UdpSocket, UdpSocket) {
+    let receiver = UdpSocket::bind((IPV6_LOOPBACK, 0)).unwrap();
+    let sender = UdpSocket::bind((IPV6_LOOPBACK, 0)).unwrap();
+    (receiver, sender)
+}
+
+#[test]
+fn peek_leaves_the_datagram_queued() {
+    let (mut receiver, mut sender) = pair();
+    sender
+        .send_to(b"hello", receiver.local_addr().unwrap())
+        .unwrap();
+
+    let mut buf = [0; 2];
+    let (len, addr) = receiver.peek_from(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"he");
+    assert_eq!(addr, sender.local_addr().unwrap());
+
+    let mut buf = [0; 16];
+    let info = receiver
+        .recv_from_with_flags(&mut buf, RecvFlags::PEEK)
+        .unwrap();
+    assert_eq!(&buf[..info.len], b"hello");
+    assert!(!info.is_truncated());
+
+    let (len, _) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"hello");
+}
+
+#[test]
+fn recv_from_with_flags_reports_truncation() {
+    let (mut receiver, mut sender) = pair();
+    sender
+        .send_to(b"hello", receiver.local_addr().unwrap())
+        .unwrap();
+
+    let mut buf = [0; 3];
+    let info = receiver
+        .recv_from_with_flags(&mut buf, RecvFlags::empty())
+        .unwrap();
+    assert_eq!(&buf, b"hel");
+    assert_eq!(info.datagram_len, 5);
+    assert!(info.is_truncated());
+}
+
+#[test]
+fn recv_buf_returns_whole_datagrams() {
+    let (mut receiver, mut sender) = pair();
+    let addr = receiver.local_addr().unwrap();
+    sender.send_to(b"first", addr).unwrap();
+    sender.send_to(b"", addr).unwrap();
+    sender.send_to(b"third", addr).unwrap();
+
+    {
+        let datagram = receiver.recv_buf().unwrap();
+        assert_eq!(&*datagram, b"first");
+        assert_eq!(datagram.addr(), sender.local_addr().unwrap());
+    }
+    assert!(receiver.recv_buf().unwrap().is_empty());
+    assert_eq!(&*receiver.recv_buf().unwrap(), b"third");
+}
+
+#[test]
+fn recv_buf_returns_a_peeked_datagram() {
+    let (mut receiver, mut sender) = pair();
+    sender
+        .send_to(b"hello", receiver.local_addr().unwrap())
+        .unwrap();
+
+    let mut buf = [0; 16];
+    receiver.peek_from(&mut buf).unwrap();
+    assert_eq!(&*receiver.recv_buf().unwrap(), b"hello");
+}